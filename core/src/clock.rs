@@ -0,0 +1,60 @@
+//! Wall clock abstraction
+//!
+//! Vault core does not read system time directly. Anything that needs the
+//! current time, e.g. `created_at` timestamps or mortality checks, receives a
+//! [`Clock`] from the caller instead.
+//!
+//! This keeps the core usable on devices without wall clock, where time
+//! comes from the host or from the signed payload, and makes time-dependent
+//! logic deterministic with [`FixedClock`].
+
+/// Source of current time
+pub trait Clock {
+    /// Current time, seconds since Unix epoch
+    fn now_unix(&self) -> u64;
+}
+
+/// [`Clock`] reading system time
+///
+/// Available only with `std` feature.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    /// System time before Unix epoch is reported as epoch itself
+    fn now_unix(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// [`Clock`] that always reports the same time
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now_unix(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_clock_reports_fixed_time() {
+        let clock = FixedClock(1_700_000_000);
+        assert_eq!(clock.now_unix(), 1_700_000_000);
+        assert_eq!(clock.now_unix(), clock.0);
+    }
+
+    #[test]
+    fn system_clock_after_epoch() {
+        assert!(SystemClock.now_unix() > 1_700_000_000);
+    }
+}
@@ -68,13 +68,20 @@
 //!
 //! Vault keeps track of the generated [`SufficientCrypto`] QR codes in
 //! history log.  
-use crate::error::DefinitionError;
+use crate::error::{DefinitionError, DefinitionResult};
 use codec::{Decode, Encode};
+use sp_core::{ecdsa, ed25519, sr25519};
+use sp_runtime::MultiSignature;
 
 /// Encryption algorithm
 ///
 /// Lists all encryption algorithms supported by Substrate
-#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq)]
+///
+/// With `serde` feature, encryption is serialized as lowercase name, same as
+/// in [`show`](Encryption::show).
+#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Encryption {
     Ed25519,
     Sr25519,
@@ -96,6 +103,14 @@ impl Encryption {
     }
 }
 
+/// `Sr25519`, used by all default networks, is the encryption for addresses
+/// without network or with network not specifying otherwise
+impl Default for Encryption {
+    fn default() -> Self {
+        Encryption::Sr25519
+    }
+}
+
 impl TryFrom<String> for Encryption {
     type Error = DefinitionError;
 
@@ -109,3 +124,175 @@ impl TryFrom<String> for Encryption {
         }
     }
 }
+
+/// Signature bundled with [`Encryption`] it was produced with
+///
+/// Alternative to [`MultiSignature`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSignature.html)
+/// for contexts where raw bytes are more convenient, e.g. FFI or json.
+/// Unlike `MultiSignature`, distinguishes `Ecdsa` and `Ethereum` signatures.
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+pub struct TaggedSignature {
+    pub encryption: Encryption,
+    pub signature: Vec<u8>,
+}
+
+impl TaggedSignature {
+    /// Generate [`TaggedSignature`] from parts: [`Encryption`] and raw
+    /// signature  
+    pub fn new(encryption: Encryption, signature: Vec<u8>) -> Self {
+        Self {
+            encryption,
+            signature,
+        }
+    }
+}
+
+impl From<MultiSignature> for TaggedSignature {
+    /// `Ecdsa` signature is tagged as `Ecdsa`, use [`TaggedSignature::new`]
+    /// for `Ethereum` signatures.
+    fn from(value: MultiSignature) -> Self {
+        match value {
+            MultiSignature::Ed25519(a) => Self::new(Encryption::Ed25519, a.as_ref().to_vec()),
+            MultiSignature::Sr25519(a) => Self::new(Encryption::Sr25519, a.as_ref().to_vec()),
+            MultiSignature::Ecdsa(a) => Self::new(Encryption::Ecdsa, a.as_ref().to_vec()),
+        }
+    }
+}
+
+impl TryFrom<TaggedSignature> for MultiSignature {
+    type Error = DefinitionError;
+
+    fn try_from(value: TaggedSignature) -> Result<Self, Self::Error> {
+        match value.encryption {
+            Encryption::Ed25519 => {
+                let into_signature: [u8; 64] = value
+                    .signature
+                    .try_into()
+                    .map_err(|_| DefinitionError::WrongSignatureLength)?;
+                Ok(MultiSignature::Ed25519(ed25519::Signature::from_raw(
+                    into_signature,
+                )))
+            }
+            Encryption::Sr25519 => {
+                let into_signature: [u8; 64] = value
+                    .signature
+                    .try_into()
+                    .map_err(|_| DefinitionError::WrongSignatureLength)?;
+                Ok(MultiSignature::Sr25519(sr25519::Signature::from_raw(
+                    into_signature,
+                )))
+            }
+            Encryption::Ecdsa | Encryption::Ethereum => {
+                let into_signature: [u8; 65] = value
+                    .signature
+                    .try_into()
+                    .map_err(|_| DefinitionError::WrongSignatureLength)?;
+                Ok(MultiSignature::Ecdsa(ecdsa::Signature::from_raw(
+                    into_signature,
+                )))
+            }
+        }
+    }
+}
+
+/// Check that raw signature length matches the [`Encryption`]
+///
+/// Signature is 64 bytes for `Ed25519` and `Sr25519`, and 65 bytes for
+/// `Ecdsa` and `Ethereum`. Should be checked for signatures received from
+/// outside, e.g. reassembled from QR code, before further processing.
+pub fn validate_signature_bytes(encryption: Encryption, bytes: &[u8]) -> DefinitionResult<()> {
+    let expected_length = match encryption {
+        Encryption::Ed25519 | Encryption::Sr25519 => 64,
+        Encryption::Ecdsa | Encryption::Ethereum => 65,
+    };
+    if bytes.len() != expected_length {
+        return Err(DefinitionError::WrongSignatureLength);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_signature_round_trip() {
+        for (encryption, length) in [
+            (Encryption::Ed25519, 64),
+            (Encryption::Sr25519, 64),
+            (Encryption::Ecdsa, 65),
+        ] {
+            let tagged = TaggedSignature::new(encryption, vec![7; length]);
+            let multi = MultiSignature::try_from(tagged.clone()).unwrap();
+            assert_eq!(TaggedSignature::from(multi), tagged);
+        }
+    }
+
+    #[test]
+    fn ethereum_signature_tagged_as_ecdsa() {
+        let tagged = TaggedSignature::new(Encryption::Ethereum, vec![7; 65]);
+        let multi = MultiSignature::try_from(tagged).unwrap();
+        assert!(matches!(multi, MultiSignature::Ecdsa(_)));
+        assert_eq!(
+            TaggedSignature::from(multi),
+            TaggedSignature::new(Encryption::Ecdsa, vec![7; 65])
+        );
+    }
+
+    #[test]
+    fn tagged_signature_length_checked() {
+        for (encryption, length) in [
+            (Encryption::Ed25519, 65),
+            (Encryption::Sr25519, 63),
+            (Encryption::Ecdsa, 64),
+            (Encryption::Ethereum, 66),
+        ] {
+            assert!(matches!(
+                MultiSignature::try_from(TaggedSignature::new(encryption, vec![7; length])),
+                Err(DefinitionError::WrongSignatureLength)
+            ));
+        }
+    }
+
+    #[test]
+    fn signature_bytes_length_checked() {
+        for (encryption, length) in [
+            (Encryption::Ed25519, 64),
+            (Encryption::Sr25519, 64),
+            (Encryption::Ecdsa, 65),
+            (Encryption::Ethereum, 65),
+        ] {
+            assert!(validate_signature_bytes(encryption, &vec![0; length]).is_ok());
+            for wrong_length in [0, length - 1, length + 1] {
+                assert!(matches!(
+                    validate_signature_bytes(encryption, &vec![0; wrong_length]),
+                    Err(DefinitionError::WrongSignatureLength)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn default_encryption_is_sr25519() {
+        assert_eq!(Encryption::default(), Encryption::Sr25519);
+        let mut encryptions = vec![
+            Encryption::Ethereum,
+            Encryption::Sr25519,
+            Encryption::Ecdsa,
+            Encryption::Ed25519,
+        ];
+        encryptions.sort();
+        assert_eq!(
+            encryptions,
+            [
+                Encryption::Ed25519,
+                Encryption::Sr25519,
+                Encryption::Ecdsa,
+                Encryption::Ethereum,
+            ]
+        );
+        for encryption in encryptions {
+            assert_eq!(Encryption::try_from(encryption.show()).unwrap(), encryption);
+        }
+    }
+}
@@ -1,24 +1,18 @@
 use codec::{Decode, Encode};
 use sp_core::H256;
 
-/// Encryption algorithm
-/// Lists all encryption algorithms supported by Substrate
-#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq)]
-pub enum Encryption {
-    Ed25519,
-    Sr25519,
-    Ecdsa,
-    Ethereum,
-}
+use crate::{clock::Clock, crypto::Encryption, error::IdentityResult, keyring::AddressKey};
 
 /// Event content for address generation or removal.
 #[derive(Debug, Decode, Encode, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IdentityRecord {
     /// The name of the seed.
     pub seed_name: String,
     /// [`Encryption`] scheme of the seed.
     pub encryption: Encryption,
     /// Public key.
+    #[cfg_attr(feature = "serde", serde(with = "sp_core::bytes"))]
     pub public_key: Vec<u8>,
     /// - path with soft (`/`) and hard (`//`) derivations only, **without** password  
     pub path: String,
@@ -59,4 +53,90 @@ impl IdentityRecord {
             network_genesis_hash,
         }
     }
+
+    /// Rebuild [`AddressKey`] of the address this record is about
+    ///
+    /// Errors if stored public key length does not match the record
+    /// [`Encryption`].
+    pub fn to_address_key(&self) -> IdentityResult<AddressKey> {
+        AddressKey::from_parts(
+            &self.public_key,
+            &self.encryption,
+            Some(self.network_genesis_hash),
+        )
+    }
+
+    /// Make [`IdentityEntry`] with current time from the `clock`
+    pub fn stamp<C: Clock>(self, clock: &C) -> IdentityEntry {
+        IdentityEntry {
+            record: self,
+            created_at: clock.now_unix(),
+        }
+    }
+}
+
+/// [`IdentityRecord`] with the time the event happened at, as stored in the
+/// history log
+#[derive(Debug, Decode, Encode, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdentityEntry {
+    /// Event content.
+    pub record: IdentityRecord,
+    /// Event time, seconds since Unix epoch.
+    pub created_at: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{clock::FixedClock, network_spec::NetworkSpecs};
+
+    /// `Sr25519` public key of `//Alice`
+    const ALICE_SR25519: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    #[test]
+    fn record_address_key() {
+        let polkadot = NetworkSpecs::polkadot();
+        let public_key = hex::decode(ALICE_SR25519).unwrap();
+        let record = IdentityRecord::get(
+            "Alice",
+            &Encryption::Sr25519,
+            &public_key,
+            "//Alice",
+            polkadot.genesis_hash,
+        );
+        assert_eq!(
+            record.to_address_key().unwrap(),
+            AddressKey::from_parts(
+                &public_key,
+                &Encryption::Sr25519,
+                Some(polkadot.genesis_hash)
+            )
+            .unwrap()
+        );
+        let ecdsa_record = IdentityRecord {
+            encryption: Encryption::Ecdsa,
+            ..record
+        };
+        assert!(ecdsa_record.to_address_key().is_err());
+    }
+
+    #[test]
+    fn record_stamped_with_clock() {
+        let record = IdentityRecord::get(
+            "Alice",
+            &Encryption::Sr25519,
+            &hex::decode(ALICE_SR25519).unwrap(),
+            "//Alice",
+            NetworkSpecs::polkadot().genesis_hash,
+        );
+        let entry = record.clone().stamp(&FixedClock(1_700_000_000));
+        assert_eq!(entry.created_at, 1_700_000_000);
+        assert_eq!(entry.record, record);
+        assert_eq!(
+            IdentityEntry::decode(&mut &entry.encode()[..]).unwrap(),
+            entry
+        );
+    }
 }
@@ -7,7 +7,13 @@ use crate::network_spec::NetworkSpecsKey;
 use crate::users::AddressDetails;
 use crate::{crypto::Encryption, keyring::AddressKey};
 
+/// Errors in address and seed phrase handling
+///
+/// Every variant must have `#[error]` message, `thiserror` does not derive
+/// `Display` otherwise. New variants are expected as the crate grows, so the
+/// enum is non-exhaustive for the downstream users.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum IdentityError {
     #[error(transparent)]
     Codec(#[from] codec::Error),
@@ -108,6 +114,66 @@ pub enum IdentityError {
         address_key: AddressKey,
     },
 
+    /// Number of words is not a valid seed phrase length: 12, 15, 18, 21 or
+    /// 24 words.
+    #[error("Invalid number of words in seed phrase: {0}.")]
+    InvalidWordCount(u32),
+
+    /// Seed phrase is not a valid BIP39 mnemonic: unknown word, wrong number
+    /// of words, or checksum mismatch.
+    ///
+    /// Phrase itself is secret and is not included.
+    #[error("Invalid recovery phrase.")]
+    InvalidMnemonic,
+
+    /// Entropy length is not valid for BIP39 mnemonic: 16, 20, 24, 28 or 32
+    /// bytes.
+    #[error("Invalid entropy length: {0} bytes.")]
+    InvalidEntropyLength(usize),
+
+    /// Position of the missing word is outside of the seed phrase.
+    #[error("Missing word index {0} is out of seed phrase.")]
+    MissingWordIndex(usize),
+
+    /// No wordlist words start with the prefix.
+    #[error("No words found for prefix {0}.")]
+    UnknownWordPrefix(String),
+
+    /// Several wordlist words start with the prefix.
+    #[error("Prefix {0} matches more than one word.")]
+    AmbiguousWordPrefix(String),
+
+    /// Encryption algorithm signs the message itself, not its hash, so the
+    /// prehashed digest could not be signed.
+    #[error("Prehashed signing is not supported for {}.", .0.show())]
+    PrehashedSigningNotSupported(Encryption),
+
+    /// [`AddressDetails`] encryption is different from the encryption of its
+    /// [`NetworkSpecsKey`] in `network_id` field.
+    #[error(
+        "Address details have encryption {}, while their network has encryption {}.",
+        details_encryption.show(),
+        network_encryption.show(),
+    )]
+    InconsistentEncryption {
+        details_encryption: Encryption,
+        network_encryption: Encryption,
+    },
+
+    /// Got empty email for email and password account.
+    #[error("Email is empty.")]
+    EmptyEmail,
+
+    /// Account password is shorter than the allowed minimum, associated
+    /// value is the minimum length.
+    #[error("Password is too short, at least {0} characters required.")]
+    PasswordTooShort(usize),
+
+    /// Derivation password is longer than the allowed maximum, associated
+    /// value is the maximum length.
+    #[error("Password is too long, at most {0} characters allowed.")]
+    PasswordTooLong(usize),
+
     /// Got empty seed phrase.
     #[error("Seed phrase empty.")]
     EmptySeed,
@@ -194,6 +260,17 @@ pub enum IdentityError {
     #[error("Derivation {0} has invalid format.")]
     InvalidDerivation(String),
 
+    /// Derivation path entered by the user has invalid format.
+    ///
+    /// Path could contain password, so it is not included.
+    #[error("Invalid derivation path format.")]
+    InvalidDerivationPath,
+
+    /// Derivation path has an empty junction, e.g. `//` with nothing after
+    /// it, which is almost always a typo.
+    #[error("Derivation path has an empty junction.")]
+    EmptyJunction,
+
     /// User was creating the derivation with password, and thus moved into
     /// `PasswordConfirm` modal, however, the password was not found when
     /// cutting password from the path.
@@ -338,7 +415,11 @@ pub enum IdentityError {
     DataPacking(String),
 }
 
+/// Errors in definitions: keys, network specs and encodings
+///
+/// Non-exhaustive, same as [`IdentityError`].
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum DefinitionError {
     #[error(transparent)]
     HexDecodingError(#[from] FromHexError),
@@ -360,6 +441,34 @@ pub enum DefinitionError {
     #[error("Public key length does not match the encryption.")]
     WrongPublicKeyLength,
 
+    /// Received 20-byte Ethereum address where Ethereum compressed public key
+    /// was expected.
+    ///
+    /// Address is a hash of the public key, public key could not be restored
+    /// from it.
+    #[error("Expected compressed public key (33 bytes), got Ethereum address (20 bytes).")]
+    EthereumAddressNotPublicKey,
+
+    /// Received Ethereum address where base58 address was expected;
+    /// Ethereum addresses carry no network prefix.
+    #[error("Ethereum address has no base58 network prefix.")]
+    EthereumAddressNoPrefix,
+
+    /// Base58 address has a network prefix, while address without network
+    /// prefix was expected.
+    #[error("Address is not in bare (no network prefix) format.")]
+    NotBareAddress,
+
+    /// Encryption has no base58 address format without network prefix, as
+    /// is the case for `Ethereum`.
+    #[error("Encryption {} has no bare base58 address format.", .0.show())]
+    NoBareAddressFormat(Encryption),
+
+    /// Received signature length is different from the one expected for
+    /// given encryption algorithm.
+    #[error("Signature length does not match the encryption.")]
+    WrongSignatureLength,
+
     /// `Error` when there is encryption mismatch between one of
     /// [`NetworkSpecsKey`] in `network_id` field of [`AddressDetails`] and the
     /// `encryption` field of [`AddressDetails`]
@@ -439,7 +548,514 @@ pub enum DefinitionError {
 
     #[error("Cannot convert {0} to valid encryption.")]
     UnknownEncryption(String),
+
+    /// Address of one [`Encryption`] could not be re-encoded as address of
+    /// another, because the keys are different.
+    #[error(
+        "Address with encryption {} could not be converted into {}.",
+        from.show(),
+        to.show(),
+    )]
+    EncryptionNotConvertible { from: Encryption, to: Encryption },
+
+    /// Ethereum address is not `0x` followed by 40 hexadecimal symbols.
+    #[error("Invalid Ethereum address format.")]
+    InvalidEthereumAddress,
+
+    /// Ethereum address does not match its EIP-55 mixed-case checksum.
+    #[error("Ethereum address checksum mismatch.")]
+    EthereumChecksumMismatch,
+
+    /// Network specs base58 prefix does not match the `SS58Prefix` constant
+    /// fetched from the chain.
+    #[error(
+        "Network {name} specs have base58 prefix {specs_prefix}, \
+        while the chain has {on_chain_prefix}."
+    )]
+    PrefixMismatch {
+        name: String,
+        specs_prefix: u16,
+        on_chain_prefix: u16,
+    },
+
+    /// Network specs update is for a different network than the local specs.
+    #[error(
+        "Network specs update has genesis hash {}, local specs have {}.",
+        hex::encode(incoming),
+        hex::encode(local)
+    )]
+    NetworkUpdateGenesisHashMismatch { local: H256, incoming: H256 },
+
+    /// Network specs base58 prefix is out of the 14-bit range base58 address
+    /// could carry, or is reserved.
+    #[error("Network specs base58 prefix {0} is invalid.")]
+    InvalidBase58Prefix(u16),
+
+    /// Network specs have empty network name.
+    #[error("Network specs name is empty.")]
+    EmptyNetworkName,
+
+    /// Network specs have empty token unit.
+    #[error("Network specs unit is empty.")]
+    EmptyNetworkUnit,
+
+    /// Network could not be removed, as addresses are bound to it.
+    ///
+    /// Associated data is the indices of such addresses, as found by
+    /// [`remove_network`](crate::network_spec::remove_network), for callers
+    /// refusing the removal.
+    #[error("Network has {} addresses bound to it.", .0.len())]
+    NetworkHasAddresses(Vec<usize>),
+
+    /// Genesis hash is all zeroes, i.e. is a placeholder rather than a real
+    /// network genesis hash.
+    #[error("Genesis hash is all zeroes.")]
+    ZeroGenesisHash,
+
+    /// [`NetworkSpecsKey`] content is not 33 bytes long, i.e. could not be an
+    /// encryption variant byte followed by genesis hash.
+    #[error("Network specs key has wrong length.")]
+    WrongNetworkSpecsKeyLength,
+
+    /// Genesis hash in the signing payload signed extensions is not the
+    /// genesis hash of the network the payload is expected to be for.
+    #[error(
+        "Signing payload is for network with genesis hash {}, expected {}.",
+        hex::encode(found),
+        hex::encode(expected)
+    )]
+    PayloadGenesisHashMismatch { expected: H256, found: H256 },
+
+    /// Signing payload is shorter than the call length declared in its
+    /// compact prefix.
+    #[error("Signing payload is shorter than declared call length.")]
+    PayloadTooShort,
+
+    /// Signing payload signed extensions do not fit the network signed
+    /// extensions layout: data is missing or left over, or is inconsistent.
+    #[error("Signing payload signed extensions do not match the network.")]
+    PayloadExtensionsMismatch,
+
+    /// Network metadata lists signed extension, that could add unknown data
+    /// to the signing payload.
+    #[error("Unknown signed extension {0}.")]
+    UnknownSignedExtension(String),
+
+    /// Network metadata lists known signed extensions, but not in the order
+    /// the signing payload layout is known for.
+    #[error("Signed extensions are in unsupported order.")]
+    UnsupportedSignedExtensionsOrder,
+
+    /// Payload is not an unsigned `derivations` import payload, i.e. does not
+    /// start with `53ffde`.
+    #[error("Payload is not a derivations import.")]
+    NotDerivationsImport,
+
+    /// Received `derivations` import payload contains an invalid or
+    /// passworded derivation.
+    ///
+    /// Associated data is the derivation, with password replaced by
+    /// `<password>` placeholder.
+    #[error("Derivation {0} has invalid format.")]
+    InvalidDerivation(String),
+
+    /// Wallet manifest is too short to contain version and checksum.
+    #[error("Wallet manifest is too short.")]
+    ManifestTooShort,
+
+    /// Wallet manifest checksum does not match its content, i.e. manifest is
+    /// damaged.
+    #[error("Wallet manifest checksum mismatch.")]
+    ManifestChecksumMismatch,
+
+    /// Wallet manifest format version is not supported.
+    #[error("Unsupported wallet manifest version {0}.")]
+    UnsupportedManifestVersion(u8),
+
+    /// Wallet manifest account fails
+    /// [`AddressDetails::validate`](crate::users::AddressDetails::validate),
+    /// e.g. its encryption does not match its network.
+    ///
+    /// Associated data is the position of the account in the manifest.
+    #[error("Wallet manifest account {0} is invalid.")]
+    InvalidManifestAccount(usize),
+
+    /// QR payload ends before its prelude, verifier public key, or content.
+    #[error("QR payload is too short.")]
+    QrPayloadTooShort,
+
+    /// QR payload is one frame of several, frames must be reassembled
+    /// before decoding.
+    #[error("QR payload is a part of multiframe QR code.")]
+    QrMultiframe,
+
+    /// QR payload does not start with Substrate marker `53`.
+    #[error("QR payload is not a Substrate payload, starts with {0:02x}.")]
+    NotSubstrateQr(u8),
+
+    /// QR payload type is not `c1` network specs update.
+    #[error("QR payload type {0:02x} is not a network specs update.")]
+    NotAddSpecsQr(u8),
+
+    /// QR payload encryption byte corresponds to no [`Encryption`] and is
+    /// not `ff` for unsigned.
+    #[error("QR payload has unknown encryption {0:02x}.")]
+    UnknownQrEncryption(u8),
+
+    /// QR payload verifier signature does not match the content.
+    #[error("QR payload signature is invalid.")]
+    QrSignatureInvalid,
 }
 
 pub type IdentityResult<T> = std::result::Result<T, IdentityError>;
 pub type DefinitionResult<T> = std::result::Result<T, DefinitionError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sp_core::{
+        crypto::{PublicError, SecretStringError},
+        sr25519,
+    };
+
+    fn name() -> String {
+        String::from("sample")
+    }
+
+    fn genesis_hash() -> H256 {
+        H256::repeat_byte(1)
+    }
+
+    fn multisigner() -> MultiSigner {
+        MultiSigner::Sr25519(sr25519::Public::from_raw([1; 32]))
+    }
+
+    fn address_key() -> AddressKey {
+        AddressKey::new(multisigner(), Some(genesis_hash()))
+    }
+
+    fn network_specs_key() -> NetworkSpecsKey {
+        NetworkSpecsKey::from_parts(&genesis_hash(), &Encryption::Sr25519).unwrap()
+    }
+
+    fn address_details() -> AddressDetails {
+        AddressDetails {
+            seed_name: name(),
+            path: String::from("//0"),
+            has_pwd: true,
+            network_id: Some(network_specs_key()),
+            encryption: Encryption::Sr25519,
+            public_key: vec![1; 32],
+            secret_exposed: false,
+        }
+    }
+
+    /// Position of the variant in [`IdentityError`]
+    ///
+    /// Match is exhaustive: new variant does not compile until it gets a
+    /// position here, and a sample in [`identity_errors`].
+    fn identity_variant(error: &IdentityError) -> usize {
+        match error {
+            IdentityError::Codec(_) => 0,
+            IdentityError::Bip39MnemonicType(_) => 1,
+            IdentityError::DefinitionsError(_) => 2,
+            IdentityError::UnexpectedGenesisHash { .. } => 3,
+            IdentityError::HistoryEntryNotFound(_) => 4,
+            IdentityError::TwoRootKeys { .. } => 5,
+            IdentityError::SeedNameNotMatching { .. } => 6,
+            IdentityError::Qr(_) => 7,
+            IdentityError::NetworkSpecsKeyForAddressNotFound { .. } => 8,
+            IdentityError::InvalidWordCount(_) => 9,
+            IdentityError::InvalidMnemonic => 10,
+            IdentityError::InvalidEntropyLength(_) => 11,
+            IdentityError::MissingWordIndex(_) => 12,
+            IdentityError::UnknownWordPrefix(_) => 13,
+            IdentityError::AmbiguousWordPrefix(_) => 14,
+            IdentityError::PrehashedSigningNotSupported(_) => 15,
+            IdentityError::InconsistentEncryption { .. } => 16,
+            IdentityError::EmptyEmail => 17,
+            IdentityError::PasswordTooShort(_) => 18,
+            IdentityError::PasswordTooLong(_) => 19,
+            IdentityError::EmptySeed => 20,
+            IdentityError::EmptySeedName => 21,
+            IdentityError::SecretStringError(_) => 22,
+            IdentityError::KeyCollisionBatch { .. } => 23,
+            IdentityError::KeyCollision { .. } => 24,
+            IdentityError::DerivationExists { .. } => 25,
+            IdentityError::InvalidDerivation(_) => 26,
+            IdentityError::InvalidDerivationPath => 27,
+            IdentityError::EmptyJunction => 28,
+            IdentityError::LostPwd => 29,
+            IdentityError::DerivationsNotFound => 30,
+            IdentityError::Sign => 31,
+            IdentityError::DifferentNamesSameGenesisHash { .. } => 32,
+            IdentityError::DifferentBase58Specs { .. } => 33,
+            IdentityError::GeneralVerifierNotFound => 34,
+            IdentityError::TypesNotFound => 35,
+            IdentityError::NetworkSpecsNotFound(_) => 36,
+            IdentityError::AddressNotFound(_) => 37,
+            IdentityError::MetaValuesNotFound { .. } => 38,
+            IdentityError::ChecksumMismatch => 39,
+            IdentityError::DangerStatusNotFound => 40,
+            IdentityError::NoKnownSeeds => 41,
+            IdentityError::SecretExposedMismatch { .. } => 42,
+            IdentityError::WrongPassword => 43,
+            IdentityError::MissingPasswordInfo(_) => 44,
+            IdentityError::NoSeedForKeyPair { .. } => 45,
+            IdentityError::NoSeedFound { .. } => 46,
+            IdentityError::NoRootKeyForSeed(_) => 47,
+            IdentityError::DataPacking(_) => 48,
+        }
+    }
+
+    /// Sample of every [`IdentityError`] variant, in variant order
+    fn identity_errors() -> Vec<IdentityError> {
+        vec![
+            IdentityError::Codec(codec::Error::from("sample")),
+            IdentityError::Bip39MnemonicType(bip39::ErrorKind::InvalidChecksum),
+            IdentityError::DefinitionsError(DefinitionError::WrongPublicKeyLength),
+            IdentityError::UnexpectedGenesisHash {
+                name: name(),
+                genesis_hash: genesis_hash(),
+            },
+            IdentityError::HistoryEntryNotFound(1),
+            IdentityError::TwoRootKeys {
+                seed_name: name(),
+                encryption: Encryption::Sr25519,
+            },
+            IdentityError::SeedNameNotMatching {
+                address_key: address_key(),
+                expected_seed_name: name(),
+                real_seed_name: name(),
+            },
+            IdentityError::Qr(name()),
+            IdentityError::NetworkSpecsKeyForAddressNotFound {
+                network_specs_key: network_specs_key(),
+                address_key: address_key(),
+            },
+            IdentityError::InvalidWordCount(13),
+            IdentityError::InvalidMnemonic,
+            IdentityError::InvalidEntropyLength(15),
+            IdentityError::MissingWordIndex(12),
+            IdentityError::UnknownWordPrefix(name()),
+            IdentityError::AmbiguousWordPrefix(name()),
+            IdentityError::PrehashedSigningNotSupported(Encryption::Sr25519),
+            IdentityError::InconsistentEncryption {
+                details_encryption: Encryption::Sr25519,
+                network_encryption: Encryption::Ed25519,
+            },
+            IdentityError::EmptyEmail,
+            IdentityError::PasswordTooShort(8),
+            IdentityError::PasswordTooLong(256),
+            IdentityError::EmptySeed,
+            IdentityError::EmptySeedName,
+            IdentityError::SecretStringError(SecretStringError::InvalidPath),
+            IdentityError::KeyCollisionBatch {
+                seed_name_existing: name(),
+                seed_name_new: name(),
+                cropped_path_existing: name(),
+                cropped_path_new: name(),
+                in_this_network: true,
+            },
+            IdentityError::KeyCollision { seed_name: name() },
+            IdentityError::DerivationExists {
+                multisigner: multisigner(),
+                address_details: Box::new(address_details()),
+                network_specs_key: network_specs_key(),
+            },
+            IdentityError::InvalidDerivation(name()),
+            IdentityError::InvalidDerivationPath,
+            IdentityError::EmptyJunction,
+            IdentityError::LostPwd,
+            IdentityError::DerivationsNotFound,
+            IdentityError::Sign,
+            IdentityError::DifferentNamesSameGenesisHash {
+                name1: name(),
+                name2: name(),
+                genesis_hash: genesis_hash(),
+            },
+            IdentityError::DifferentBase58Specs {
+                base58_1: 0,
+                base58_2: 2,
+                genesis_hash: genesis_hash(),
+            },
+            IdentityError::GeneralVerifierNotFound,
+            IdentityError::TypesNotFound,
+            IdentityError::NetworkSpecsNotFound(network_specs_key()),
+            IdentityError::AddressNotFound(address_key()),
+            IdentityError::MetaValuesNotFound {
+                name: name(),
+                version: 1,
+            },
+            IdentityError::ChecksumMismatch,
+            IdentityError::DangerStatusNotFound,
+            IdentityError::NoKnownSeeds,
+            IdentityError::SecretExposedMismatch {
+                multisigner: multisigner(),
+                address_details: address_details(),
+            },
+            IdentityError::WrongPassword,
+            IdentityError::MissingPasswordInfo(name()),
+            IdentityError::NoSeedForKeyPair {
+                multisigner: multisigner(),
+            },
+            IdentityError::NoSeedFound {
+                multisigner: multisigner(),
+            },
+            IdentityError::NoRootKeyForSeed(name()),
+            IdentityError::DataPacking(name()),
+        ]
+    }
+
+    /// Position of the variant in [`DefinitionError`]
+    ///
+    /// Match is exhaustive: new variant does not compile until it gets a
+    /// position here, and a sample in [`definition_errors`].
+    fn definition_variant(error: &DefinitionError) -> usize {
+        match error {
+            DefinitionError::HexDecodingError(_) => 0,
+            DefinitionError::CodecError(_) => 1,
+            DefinitionError::LibSecp(_) => 2,
+            DefinitionError::Ss58(_) => 3,
+            DefinitionError::Io(_) => 4,
+            DefinitionError::WrongPublicKeyLength => 5,
+            DefinitionError::EthereumAddressNotPublicKey => 6,
+            DefinitionError::EthereumAddressNoPrefix => 7,
+            DefinitionError::NotBareAddress => 8,
+            DefinitionError::NoBareAddressFormat(_) => 9,
+            DefinitionError::WrongSignatureLength => 10,
+            DefinitionError::EncryptionMismatch { .. } => 11,
+            DefinitionError::SpecsGenesisHashMismatch { .. } => 12,
+            DefinitionError::SpecsToSendEncryptionMismatch { .. } => 13,
+            DefinitionError::SpecsToSendGenesisHash { .. } => 14,
+            DefinitionError::MetadataMismatch { .. } => 15,
+            DefinitionError::UnknownEncryption(_) => 16,
+            DefinitionError::EncryptionNotConvertible { .. } => 17,
+            DefinitionError::InvalidEthereumAddress => 18,
+            DefinitionError::EthereumChecksumMismatch => 19,
+            DefinitionError::PrefixMismatch { .. } => 20,
+            DefinitionError::NetworkUpdateGenesisHashMismatch { .. } => 21,
+            DefinitionError::InvalidBase58Prefix(_) => 22,
+            DefinitionError::EmptyNetworkName => 23,
+            DefinitionError::EmptyNetworkUnit => 24,
+            DefinitionError::NetworkHasAddresses(_) => 25,
+            DefinitionError::ZeroGenesisHash => 26,
+            DefinitionError::WrongNetworkSpecsKeyLength => 27,
+            DefinitionError::PayloadGenesisHashMismatch { .. } => 28,
+            DefinitionError::PayloadTooShort => 29,
+            DefinitionError::PayloadExtensionsMismatch => 30,
+            DefinitionError::UnknownSignedExtension(_) => 31,
+            DefinitionError::UnsupportedSignedExtensionsOrder => 32,
+            DefinitionError::NotDerivationsImport => 33,
+            DefinitionError::InvalidDerivation(_) => 34,
+            DefinitionError::ManifestTooShort => 35,
+            DefinitionError::ManifestChecksumMismatch => 36,
+            DefinitionError::UnsupportedManifestVersion(_) => 37,
+            DefinitionError::InvalidManifestAccount(_) => 38,
+            DefinitionError::QrPayloadTooShort => 39,
+            DefinitionError::QrMultiframe => 40,
+            DefinitionError::NotSubstrateQr(_) => 41,
+            DefinitionError::NotAddSpecsQr(_) => 42,
+            DefinitionError::UnknownQrEncryption(_) => 43,
+            DefinitionError::QrSignatureInvalid => 44,
+        }
+    }
+
+    /// Sample of every [`DefinitionError`] variant, in variant order
+    fn definition_errors() -> Vec<DefinitionError> {
+        vec![
+            DefinitionError::HexDecodingError(FromHexError::OddLength),
+            DefinitionError::CodecError(codec::Error::from("sample")),
+            DefinitionError::LibSecp(libsecp256k1::Error::InvalidSignature),
+            DefinitionError::Ss58(PublicError::BadBase58),
+            DefinitionError::Io(std::io::Error::other("sample")),
+            DefinitionError::WrongPublicKeyLength,
+            DefinitionError::EthereumAddressNotPublicKey,
+            DefinitionError::EthereumAddressNoPrefix,
+            DefinitionError::NotBareAddress,
+            DefinitionError::NoBareAddressFormat(Encryption::Ethereum),
+            DefinitionError::WrongSignatureLength,
+            DefinitionError::EncryptionMismatch {
+                address_key: address_key(),
+                encryption: Encryption::Ed25519,
+            },
+            DefinitionError::SpecsGenesisHashMismatch {
+                network_specs_key: network_specs_key(),
+                genesis_hash: genesis_hash(),
+            },
+            DefinitionError::SpecsToSendEncryptionMismatch {
+                network_specs_key: network_specs_key(),
+                encryption: Encryption::Ed25519,
+            },
+            DefinitionError::SpecsToSendGenesisHash {
+                network_specs_key: network_specs_key(),
+                genesis_hash: genesis_hash(),
+            },
+            DefinitionError::MetadataMismatch {
+                this_name: name(),
+                this_version: 1,
+                that_name: name(),
+                that_version: 2,
+            },
+            DefinitionError::UnknownEncryption(name()),
+            DefinitionError::EncryptionNotConvertible {
+                from: Encryption::Sr25519,
+                to: Encryption::Ethereum,
+            },
+            DefinitionError::InvalidEthereumAddress,
+            DefinitionError::EthereumChecksumMismatch,
+            DefinitionError::PrefixMismatch {
+                name: name(),
+                specs_prefix: 0,
+                on_chain_prefix: 2,
+            },
+            DefinitionError::NetworkUpdateGenesisHashMismatch {
+                local: genesis_hash(),
+                incoming: H256::repeat_byte(2),
+            },
+            DefinitionError::InvalidBase58Prefix(46),
+            DefinitionError::EmptyNetworkName,
+            DefinitionError::EmptyNetworkUnit,
+            DefinitionError::NetworkHasAddresses(vec![0, 2]),
+            DefinitionError::ZeroGenesisHash,
+            DefinitionError::WrongNetworkSpecsKeyLength,
+            DefinitionError::PayloadGenesisHashMismatch {
+                expected: genesis_hash(),
+                found: H256::repeat_byte(2),
+            },
+            DefinitionError::PayloadTooShort,
+            DefinitionError::PayloadExtensionsMismatch,
+            DefinitionError::UnknownSignedExtension(name()),
+            DefinitionError::UnsupportedSignedExtensionsOrder,
+            DefinitionError::NotDerivationsImport,
+            DefinitionError::InvalidDerivation(name()),
+            DefinitionError::ManifestTooShort,
+            DefinitionError::ManifestChecksumMismatch,
+            DefinitionError::UnsupportedManifestVersion(2),
+            DefinitionError::InvalidManifestAccount(1),
+            DefinitionError::QrPayloadTooShort,
+            DefinitionError::QrMultiframe,
+            DefinitionError::NotSubstrateQr(0x54),
+            DefinitionError::NotAddSpecsQr(0x80),
+            DefinitionError::UnknownQrEncryption(0x04),
+            DefinitionError::QrSignatureInvalid,
+        ]
+    }
+
+    #[test]
+    fn identity_errors_display() {
+        for (position, error) in identity_errors().iter().enumerate() {
+            assert_eq!(identity_variant(error), position);
+            assert!(!error.to_string().is_empty(), "{error:?}");
+        }
+    }
+
+    #[test]
+    fn definition_errors_display() {
+        for (position, error) in definition_errors().iter().enumerate() {
+            assert_eq!(definition_variant(error), position);
+            assert!(!error.to_string().is_empty(), "{error:?}");
+        }
+    }
+}
@@ -1,16 +1,19 @@
 //! Common helper functions
 
 use hex;
+use sp_core::Pair;
 use sp_core::{
-    crypto::{Ss58AddressFormat, Ss58Codec},
-    hexdisplay::HexDisplay,
+    crypto::{PublicError, Ss58AddressFormat, Ss58AddressFormatRegistry, Ss58Codec},
+    hashing::{blake2_128, blake2_256, blake2_512, keccak_256, twox_128},
     Hasher, KeccakHasher, H160, H256,
 };
 use sp_core::{ecdsa, ed25519, sr25519};
-use sp_runtime::MultiSigner;
+use sp_runtime::{MultiSignature, MultiSigner};
 use std::convert::TryInto;
 
 use crate::error::DefinitionError;
+use crate::keyring::AddressKey;
+use crate::network_spec::NetworkSpecs;
 use crate::{crypto::Encryption, error::DefinitionResult};
 
 /// Decode hexadecimal `&str` into `Vec<u8>`, with descriptive error  
@@ -20,9 +23,16 @@ use crate::{crypto::Encryption, error::DefinitionResult};
 /// In addition to encoded `&str` required is input of `T::NotHex`, to produce
 /// error with details on what exactly turned out to be invalid hexadecimal
 /// string.  
+///
+/// Prefix `0x` (or `0X`, as some tools print it) is optional, so that keys
+/// pasted from block explorers and keys produced by other tools decode into
+/// same bytes. All `from_hex` constructors go through this function.
 pub fn unhex(hex_entry: &str) -> DefinitionResult<Vec<u8>> {
     let hex_entry = {
-        if let Some(a) = hex_entry.strip_prefix("0x") {
+        if let Some(a) = hex_entry
+            .strip_prefix("0x")
+            .or_else(|| hex_entry.strip_prefix("0X"))
+        {
             a
         } else {
             hex_entry
@@ -41,6 +51,17 @@ pub fn multisigner_to_public(m: &MultiSigner) -> Vec<u8> {
     }
 }
 
+/// Get identicon input bytes for
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+///
+/// Polkadot identicon libraries, e.g. `plot_icon` and `polkadot{.js}`, hash
+/// the raw public key: 32 bytes for `Ed25519` and `Sr25519`, 33 bytes of
+/// compressed key for `Ecdsa`. Ethereum wallets render blockies from the
+/// account address instead, see [`ecdsa_public_to_eth_address`].
+pub fn identicon_input(multisigner: &MultiSigner) -> Vec<u8> {
+    multisigner_to_public(multisigner)
+}
+
 /// Get [`Encryption`](crate::crypto::Encryption) from
 /// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)  
 pub fn multisigner_to_encryption(m: &MultiSigner) -> Encryption {
@@ -51,8 +72,19 @@ pub fn multisigner_to_encryption(m: &MultiSigner) -> Encryption {
     }
 }
 
+/// Get [`AddressKey`](crate::keyring::AddressKey) from
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+/// and network genesis hash, `None` for root address
+pub fn multisigner_to_address_key(m: &MultiSigner, genesis_hash: Option<H256>) -> AddressKey {
+    AddressKey::new(m.to_owned(), genesis_hash)
+}
+
 /// Get [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
 /// from public key and [`Encryption`](crate::crypto::Encryption)
+///
+/// `Ecdsa` and `Ethereum` expect 33-byte compressed public key. For
+/// `Ethereum`, 20-byte input is the account address rather than the public
+/// key, and results in a separate error.
 pub fn get_multisigner(public: &[u8], encryption: &Encryption) -> DefinitionResult<MultiSigner> {
     match encryption {
         Encryption::Ed25519 => {
@@ -70,6 +102,9 @@ pub fn get_multisigner(public: &[u8], encryption: &Encryption) -> DefinitionResu
             Ok(MultiSigner::Sr25519(sr25519::Public::from_raw(into_pubkey)))
         }
         Encryption::Ecdsa | Encryption::Ethereum => {
+            if *encryption == Encryption::Ethereum && public.len() == 20 {
+                return Err(DefinitionError::EthereumAddressNotPublicKey);
+            }
             let into_pubkey: [u8; 33] = public
                 .to_vec()
                 .try_into()
@@ -87,52 +122,85 @@ pub fn get_multisigner(public: &[u8], encryption: &Encryption) -> DefinitionResu
 /// network-specific base58 prefix by providing `Some(value)` as `optional_prefix` or with
 /// [default](https://docs.rs/sp-core/6.0.0/sp_core/crypto/trait.Ss58Codec.html#method.to_ss58check)
 /// one by leaving it `None`.
+///
+/// `Ethereum` addresses are printed in EIP-55 checksummed form; this could
+/// fail if the public key is not a valid `ecdsa` point.
 pub fn print_multisigner_as_base58_or_eth(
     multi_signer: &MultiSigner,
     optional_prefix: Option<u16>,
     encryption: Encryption,
-) -> String {
+) -> DefinitionResult<String> {
     match optional_prefix {
         Some(base58prefix) => {
             let version_for_base58 = Ss58AddressFormat::custom(base58prefix);
             match multi_signer {
                 MultiSigner::Ed25519(pubkey) => {
-                    pubkey.to_ss58check_with_version(version_for_base58)
+                    Ok(pubkey.to_ss58check_with_version(version_for_base58))
                 }
                 MultiSigner::Sr25519(pubkey) => {
-                    pubkey.to_ss58check_with_version(version_for_base58)
+                    Ok(pubkey.to_ss58check_with_version(version_for_base58))
                 }
                 MultiSigner::Ecdsa(pubkey) => {
                     if encryption == Encryption::Ethereum {
                         print_ethereum_address(pubkey)
                     } else {
-                        pubkey.to_ss58check_with_version(version_for_base58)
+                        Ok(pubkey.to_ss58check_with_version(version_for_base58))
                     }
                 }
             }
         }
         None => match multi_signer {
             MultiSigner::Ed25519(pubkey) => {
-                let version = Ss58AddressFormat::try_from("BareEd25519")
-                    .expect("unable to make Ss58AddressFormat from `BareEd25519`");
-                pubkey.to_ss58check_with_version(version)
+                let version =
+                    Ss58AddressFormat::from(Ss58AddressFormatRegistry::BareEd25519Account);
+                Ok(pubkey.to_ss58check_with_version(version))
             }
             MultiSigner::Sr25519(pubkey) => {
-                let version = Ss58AddressFormat::try_from("BareSr25519")
-                    .expect("unable to make Ss58AddressFormat from `BareSr25519`");
-                pubkey.to_ss58check_with_version(version)
+                let version =
+                    Ss58AddressFormat::from(Ss58AddressFormatRegistry::BareSr25519Account);
+                Ok(pubkey.to_ss58check_with_version(version))
             }
             MultiSigner::Ecdsa(pubkey) => {
                 if encryption == Encryption::Ethereum {
                     print_ethereum_address(pubkey)
                 } else {
-                    pubkey.to_ss58check()
+                    Ok(pubkey.to_ss58check())
                 }
             }
         },
     }
 }
 
+/// Parse base58 address without network prefix back into
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+///
+/// Reverses [`print_multisigner_as_base58_or_eth`] with `None` prefix, used
+/// for root keys: `BareEd25519` and `BareSr25519` formats for `Ed25519` and
+/// `Sr25519`, default format for `Ecdsa`. `Ethereum` has no base58 address
+/// without network prefix, and results in error.
+pub fn parse_bare_ss58(s: &str, encryption: &Encryption) -> DefinitionResult<MultiSigner> {
+    match encryption {
+        Encryption::Ed25519 => {
+            let (pubkey, version) = ed25519::Public::from_ss58check_with_version(s)?;
+            let expected = Ss58AddressFormat::from(Ss58AddressFormatRegistry::BareEd25519Account);
+            if version != expected {
+                return Err(DefinitionError::NotBareAddress);
+            }
+            Ok(MultiSigner::Ed25519(pubkey))
+        }
+        Encryption::Sr25519 => {
+            let (pubkey, version) = sr25519::Public::from_ss58check_with_version(s)?;
+            let expected = Ss58AddressFormat::from(Ss58AddressFormatRegistry::BareSr25519Account);
+            if version != expected {
+                return Err(DefinitionError::NotBareAddress);
+            }
+            Ok(MultiSigner::Sr25519(pubkey))
+        }
+        Encryption::Ecdsa => Ok(MultiSigner::Ecdsa(ecdsa::Public::from_ss58check(s)?)),
+        Encryption::Ethereum => Err(DefinitionError::NoBareAddressFormat(*encryption)),
+    }
+}
+
 /// Turn a `ecdsa::Public` addr into an Ethereum address.
 pub fn ecdsa_public_to_eth_address(public: &ecdsa::Public) -> DefinitionResult<H160> {
     let decompressed = libsecp256k1::PublicKey::parse_compressed(&public.0)?.serialize();
@@ -143,13 +211,121 @@ pub fn ecdsa_public_to_eth_address(public: &ecdsa::Public) -> DefinitionResult<H
     )))
 }
 
-/// Print a `ecdsa::Public` into `String`.
+/// Print a `ecdsa::Public` into `String` as EIP-55 checksummed Ethereum
+/// address.
 ///
-/// Panics if provided ecdsa public key is in wrong format.
-fn print_ethereum_address(public: &ecdsa::Public) -> String {
-    let account = ecdsa_public_to_eth_address(public).expect("Wrong ecdsa public key provided");
+/// Errors if provided ecdsa public key is in wrong format.
+fn print_ethereum_address(public: &ecdsa::Public) -> DefinitionResult<String> {
+    let account = ecdsa_public_to_eth_address(public)?;
+    Ok(format!("0x{}", eip55_checksum(&account)))
+}
 
-    format!("0x{:?}", HexDisplay::from(&account.as_bytes()))
+/// Hash `message` the way Ethereum `personal_sign` does
+///
+/// Hashed is `"\x19Ethereum Signed Message:\n" + len + message`, with `len`
+/// being decimal message length in bytes.
+pub fn eth_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    keccak_256(&prefixed)
+}
+
+/// Recover Ethereum address of the signer from `personal_sign` signature of
+/// `message`
+///
+/// Signature is 65-byte `r || s || v`. Both `v` forms are accepted: `27` or
+/// `28`, as produced by `personal_sign`, and raw recovery id `0` or `1`.
+pub fn eth_recover(message: &[u8], signature: &[u8; 65]) -> DefinitionResult<H160> {
+    let digest = libsecp256k1::Message::parse(&eth_message_hash(message));
+    let parsed_signature = libsecp256k1::Signature::parse_standard_slice(&signature[..64])?;
+    let v = signature[64];
+    let recovery_id = libsecp256k1::RecoveryId::parse(if v >= 27 { v - 27 } else { v })?;
+    let public = libsecp256k1::recover(&digest, &parsed_signature, &recovery_id)?;
+    ecdsa_public_to_eth_address(&ecdsa::Public::from_raw(public.serialize_compressed()))
+}
+
+/// Check if address of one [`Encryption`] could be re-encoded as address of
+/// another
+///
+/// Only `Ecdsa` and `Ethereum` addresses are built from the same key, and
+/// differ in display format only. Keys of other encryption algorithms are
+/// generated differently from the same seed and derivation path, and could
+/// not be obtained from each other: there is no way to get `Ed25519` address
+/// from `Sr25519` one.
+pub fn can_convert_encryption(from: Encryption, to: Encryption) -> bool {
+    matches!(
+        (from, to),
+        (Encryption::Ecdsa, Encryption::Ethereum) | (Encryption::Ethereum, Encryption::Ecdsa)
+    )
+}
+
+/// Re-encode `Ecdsa` [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+/// as EIP-55 checksummed Ethereum address
+pub fn ecdsa_to_ethereum_address(multi_signer: &MultiSigner) -> DefinitionResult<String> {
+    match multi_signer {
+        MultiSigner::Ecdsa(pubkey) => print_ethereum_address(pubkey),
+        _ => Err(DefinitionError::EncryptionNotConvertible {
+            from: multisigner_to_encryption(multi_signer),
+            to: Encryption::Ethereum,
+        }),
+    }
+}
+
+/// Policy for EIP-55 mixed-case checksum in Ethereum addresses
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChecksumPolicy {
+    /// Address must be exactly in EIP-55 checksummed form.
+    Require,
+
+    /// All-lowercase and all-uppercase addresses are accepted without
+    /// checksum, mixed-case addresses must pass EIP-55 checksum.
+    Lenient,
+}
+
+/// Parse `0x`-prefixed Ethereum address
+///
+/// Mixed-case address is always checked against EIP-55 checksum, so that a
+/// typo in the address is caught. Single-case addresses carry no checksum,
+/// and are accepted only with [`ChecksumPolicy::Lenient`].
+pub fn parse_eth_address(address: &str, policy: ChecksumPolicy) -> DefinitionResult<H160> {
+    let hex_part = address
+        .strip_prefix("0x")
+        .filter(|a| a.len() == 40 && a.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or(DefinitionError::InvalidEthereumAddress)?;
+    let account = H160::from_slice(&hex::decode(hex_part)?);
+    let single_case = !hex_part.bytes().any(|b| b.is_ascii_uppercase())
+        || !hex_part.bytes().any(|b| b.is_ascii_lowercase());
+    let checksum_skipped = policy == ChecksumPolicy::Lenient && single_case;
+    if !checksum_skipped && eip55_checksum(&account) != hex_part {
+        return Err(DefinitionError::EthereumChecksumMismatch);
+    }
+    Ok(account)
+}
+
+/// Get EIP-55 mixed-case checksummed hexadecimal address, without `0x`
+/// prefix
+///
+/// Letter at position `i` is uppercased if `i`-th nibble of `keccak256` hash
+/// of the lowercase hexadecimal address is `8` or above.
+fn eip55_checksum(account: &H160) -> String {
+    let lowercase = hex::encode(account.as_bytes());
+    let hash = keccak_256(lowercase.as_bytes());
+    lowercase
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
 }
 
 pub fn base58_or_eth_to_multisigner(
@@ -171,3 +347,796 @@ pub fn base58_or_eth_to_multisigner(
         }
     }
 }
+
+/// Get sr25519 [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+/// from base58 address
+///
+/// Convenience for the most common case, same as
+/// [`base58_or_eth_to_multisigner`] with `Encryption::Sr25519`.
+///
+/// Decoded public key length is checked to be 32 bytes, addresses of
+/// different length produce `Ss58` error.
+pub fn sr25519_from_ss58(address: &str) -> DefinitionResult<MultiSigner> {
+    let pubkey = sr25519::Public::from_ss58check(address)?;
+    Ok(MultiSigner::Sr25519(pubkey))
+}
+
+/// Check base58 encoding and checksum of a base58 address, without building
+/// the public key
+///
+/// Faster first-pass validation of large address lists. Address is valid
+/// here exactly when it could be decoded into public key of 32 bytes
+/// (`Ed25519` and `Sr25519`) or 33 bytes (`Ecdsa`) with a non-reserved
+/// prefix.
+pub fn ss58_checksum_valid(address: &str) -> bool {
+    ss58_prefix_and_key_len(address).is_ok()
+}
+
+/// Decode base58 address prefix and public key length, checking the
+/// checksum
+///
+/// Prefix must not be reserved, and public key must be 32 or 33 bytes long.
+fn ss58_prefix_and_key_len(address: &str) -> Result<(u16, usize), PublicError> {
+    let data = bs58::decode(address)
+        .into_vec()
+        .map_err(|_| PublicError::BadBase58)?;
+    if data.len() < 2 {
+        return Err(PublicError::BadLength);
+    }
+    let (prefix_len, prefix) = match data[0] {
+        0..=63 => (1, data[0] as u16),
+        64..=127 => {
+            let lower = (data[0] << 2) | (data[1] >> 6);
+            let upper = data[1] & 0b0011_1111;
+            (2, (lower as u16) | ((upper as u16) << 8))
+        }
+        _ => return Err(PublicError::InvalidPrefix),
+    };
+    if Ss58AddressFormat::custom(prefix).is_reserved() {
+        return Err(PublicError::FormatNotAllowed);
+    }
+    let key_len = match data.len().checked_sub(prefix_len + SS58_CHECKSUM_LEN) {
+        Some(key_len @ (32 | 33)) => key_len,
+        _ => return Err(PublicError::BadLength),
+    };
+    let checked_len = prefix_len + key_len;
+    let hash = blake2_512(&[SS58_HASH_PREFIX, &data[..checked_len]].concat());
+    if data[checked_len..] != hash[..SS58_CHECKSUM_LEN] {
+        return Err(PublicError::InvalidChecksum);
+    }
+    Ok((prefix, key_len))
+}
+
+/// Get network prefix of base58 address, e.g. to find the network of the
+/// pasted address among [`NetworkSpecs`] by `base58prefix`
+///
+/// Encryption is not needed: prefix is decoded together with the checksum
+/// check only, public key is not built.
+pub fn prefix_from_address(address: &str) -> DefinitionResult<u16> {
+    if address.starts_with("0x") {
+        return Err(DefinitionError::EthereumAddressNoPrefix);
+    }
+    let (prefix, _) = ss58_prefix_and_key_len(address)?;
+    Ok(prefix)
+}
+
+/// Detect [`Encryption`] of an address, without knowing it in advance
+///
+/// `0x`-prefixed address is `Ethereum`. Base58 address with 33-byte public
+/// key is `Ecdsa`. Base58 address with 32-byte public key could be both
+/// `Sr25519` and `Ed25519`, there is no way to tell them apart, and
+/// `Sr25519` is assumed, see [`encryption_from_address_with_hint`] to
+/// choose otherwise.
+pub fn encryption_from_address(address: &str) -> DefinitionResult<Encryption> {
+    encryption_from_address_with_hint(address, Encryption::Sr25519)
+}
+
+/// Detect [`Encryption`] of an address, same as [`encryption_from_address`],
+/// with `hint` used for 32-byte public keys
+///
+/// Hint is used only if it is `Ed25519` or `Sr25519`, other hints fall back
+/// to `Sr25519`.
+pub fn encryption_from_address_with_hint(
+    address: &str,
+    hint: Encryption,
+) -> DefinitionResult<Encryption> {
+    if address.starts_with("0x") {
+        parse_eth_address(address, ChecksumPolicy::Lenient)?;
+        return Ok(Encryption::Ethereum);
+    }
+    match ss58_prefix_and_key_len(address)? {
+        (_, 33) => Ok(Encryption::Ecdsa),
+        _ => match hint {
+            Encryption::Ed25519 => Ok(Encryption::Ed25519),
+            _ => Ok(Encryption::Sr25519),
+        },
+    }
+}
+
+/// Prefix mixed into base58 address checksum hash
+const SS58_HASH_PREFIX: &[u8] = b"SS58PRE";
+
+/// Number of checksum bytes in base58 address
+const SS58_CHECKSUM_LEN: usize = 2;
+
+/// All representations of a public key, for inspection
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PublicKeyForms {
+    /// raw public key
+    pub raw: Vec<u8>,
+
+    /// hexadecimal public key, without `0x` prefix
+    pub hex: String,
+
+    /// base58 address, if the encryption is not `Ethereum`
+    pub ss58: Option<String>,
+
+    /// Ethereum address, if the encryption is `Ethereum`
+    pub eth: Option<String>,
+}
+
+/// Get [`PublicKeyForms`] for
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+///
+/// Encryption and base58 prefix are taken from the network specs if provided.
+/// Without network specs, the encryption is the one of the `MultiSigner`, and
+/// base58 address has no network prefix.
+pub fn public_key_forms(
+    multi_signer: &MultiSigner,
+    specs: Option<&NetworkSpecs>,
+) -> PublicKeyForms {
+    let raw = multisigner_to_public(multi_signer);
+    let hex = hex::encode(&raw);
+    let encryption = specs
+        .map(|specs| specs.encryption)
+        .unwrap_or_else(|| multisigner_to_encryption(multi_signer));
+    let (ss58, eth) = if encryption == Encryption::Ethereum {
+        (None, ecdsa_to_ethereum_address(multi_signer).ok())
+    } else {
+        let address = print_multisigner_as_base58_or_eth(
+            multi_signer,
+            specs.map(|specs| specs.base58prefix),
+            encryption,
+        );
+        (address.ok(), None)
+    };
+    PublicKeyForms {
+        raw,
+        hex,
+        ss58,
+        eth,
+    }
+}
+
+/// Get on-chain account id for
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+///
+/// Account id is the public key itself for `Ed25519` and `Sr25519`,
+/// `blake2_256` hash of the compressed public key for `Ecdsa`, and 20-byte
+/// Ethereum address for `Ethereum`.
+pub fn account_id(multi_signer: &MultiSigner, encryption: Encryption) -> DefinitionResult<Vec<u8>> {
+    match multi_signer {
+        MultiSigner::Ed25519(pubkey) => Ok(pubkey.to_vec()),
+        MultiSigner::Sr25519(pubkey) => Ok(pubkey.to_vec()),
+        MultiSigner::Ecdsa(pubkey) => {
+            if encryption == Encryption::Ethereum {
+                Ok(ecdsa_public_to_eth_address(pubkey)?.as_bytes().to_vec())
+            } else {
+                Ok(blake2_256(&pubkey.0).to_vec())
+            }
+        }
+    }
+}
+
+/// Get displayable address and on-chain account id of
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+/// in given network, together
+///
+/// For `Ethereum` networks both are the same 20-byte account, once printed
+/// and once raw, and it is computed only once.
+pub fn address_and_account_id(
+    multisigner: &MultiSigner,
+    specs: &NetworkSpecs,
+) -> DefinitionResult<(String, Vec<u8>)> {
+    match (multisigner, specs.encryption) {
+        (MultiSigner::Ecdsa(pubkey), Encryption::Ethereum) => {
+            let account = ecdsa_public_to_eth_address(pubkey)?;
+            Ok((
+                format!("0x{}", eip55_checksum(&account)),
+                account.as_bytes().to_vec(),
+            ))
+        }
+        _ => Ok((
+            print_multisigner_as_base58_or_eth(
+                multisigner,
+                Some(specs.base58prefix),
+                specs.encryption,
+            )?,
+            account_id(multisigner, specs.encryption)?,
+        )),
+    }
+}
+
+/// Get `system.account` storage key for
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+/// in given network
+///
+/// Storage key is `twox128("System") ++ twox128("Account") ++
+/// blake2_128_concat(account_id)`, it is used to query the account balance.
+pub fn account_storage_key(
+    multi_signer: &MultiSigner,
+    specs: &NetworkSpecs,
+) -> DefinitionResult<Vec<u8>> {
+    let account_id = account_id(multi_signer, specs.encryption)?;
+    Ok([
+        &twox_128(b"System")[..],
+        &twox_128(b"Account")[..],
+        &blake2_128(&account_id)[..],
+        &account_id[..],
+    ]
+    .concat())
+}
+
+/// Signing context used by Substrate for `Sr25519` signatures
+const SR25519_SIGNING_CTX: &[u8] = b"substrate";
+
+/// Verify a set of signatures, each made by its own
+/// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+/// over its own message
+///
+/// Returns verification result for each item, in input order. Signature
+/// with encryption not matching the signer is invalid.
+///
+/// All `Sr25519` items are checked in a single `schnorrkel` batch first. If
+/// the batch fails, at least one of them is invalid, and the `Sr25519` items
+/// are re-checked one by one to find which. Other encryptions are always
+/// checked individually.
+pub fn verify_batch(items: &[(MultiSigner, Vec<u8>, MultiSignature)]) -> Vec<bool> {
+    let sr25519_items: Vec<(&sr25519::Public, &[u8], &sr25519::Signature)> = items
+        .iter()
+        .filter_map(|(signer, message, signature)| match (signer, signature) {
+            (MultiSigner::Sr25519(public), MultiSignature::Sr25519(signature)) => {
+                Some((public, message.as_slice(), signature))
+            }
+            _ => None,
+        })
+        .collect();
+    let sr25519_batch_valid = !sr25519_items.is_empty() && sr25519_batch_is_valid(&sr25519_items);
+
+    items
+        .iter()
+        .map(|(signer, message, signature)| match (signer, signature) {
+            (MultiSigner::Sr25519(_), MultiSignature::Sr25519(_)) if sr25519_batch_valid => true,
+            _ => verify_signature(signer, message, signature),
+        })
+        .collect()
+}
+
+/// Verify `signature` of `message` made by `public`
+///
+/// Never errors: signature of encryption not matching the signer, as well as
+/// malformed signature or key, is simply invalid. Host application could use
+/// this to check a signature produced by Vault before broadcasting it.
+pub fn verify_signature(public: &MultiSigner, message: &[u8], signature: &MultiSignature) -> bool {
+    match (public, signature) {
+        (MultiSigner::Ed25519(public), MultiSignature::Ed25519(signature)) => {
+            ed25519::Pair::verify(signature, message, public)
+        }
+        (MultiSigner::Sr25519(public), MultiSignature::Sr25519(signature)) => {
+            sr25519::Pair::verify(signature, message, public)
+        }
+        (MultiSigner::Ecdsa(public), MultiSignature::Ecdsa(signature)) => {
+            ecdsa::Pair::verify(signature, message, public)
+        }
+        _ => false,
+    }
+}
+
+/// Check all `Sr25519` signatures at once, `true` only if all are valid
+fn sr25519_batch_is_valid(items: &[(&sr25519::Public, &[u8], &sr25519::Signature)]) -> bool {
+    let mut transcripts = Vec::with_capacity(items.len());
+    let mut signatures = Vec::with_capacity(items.len());
+    let mut public_keys = Vec::with_capacity(items.len());
+    for (public, message, signature) in items {
+        let (Ok(public), Ok(signature)) = (
+            schnorrkel::PublicKey::from_bytes(&public.0),
+            schnorrkel::Signature::from_bytes(&signature.0),
+        ) else {
+            return false;
+        };
+        transcripts.push(schnorrkel::signing_context(SR25519_SIGNING_CTX).bytes(message));
+        signatures.push(signature);
+        public_keys.push(public);
+    }
+    schnorrkel::verify_batch(transcripts, &signatures, &public_keys, false).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Sr25519` public key of `//Alice`
+    const ALICE_SR25519: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    /// `Ecdsa` compressed public key of `//Alice`
+    const ALICE_ECDSA: &str = "020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a1";
+
+    fn alice(encryption: Encryption) -> MultiSigner {
+        let public = match encryption {
+            Encryption::Ecdsa | Encryption::Ethereum => ALICE_ECDSA,
+            _ => ALICE_SR25519,
+        };
+        get_multisigner(&hex::decode(public).unwrap(), &encryption).unwrap()
+    }
+
+    #[test]
+    fn bare_ss58_round_trip() {
+        let sr25519 = alice(Encryption::Sr25519);
+        let bare = print_multisigner_as_base58_or_eth(&sr25519, None, Encryption::Sr25519).unwrap();
+        assert_eq!(bare, "BauKu2iL4fncgfy22YSLGc1aDLpyuUUe5z8yNF2pDtLNr4E");
+        assert_eq!(
+            parse_bare_ss58(&bare, &Encryption::Sr25519).unwrap(),
+            sr25519
+        );
+
+        let ecdsa = alice(Encryption::Ecdsa);
+        let bare = print_multisigner_as_base58_or_eth(&ecdsa, None, Encryption::Ecdsa).unwrap();
+        assert_eq!(bare, "KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL");
+        assert_eq!(parse_bare_ss58(&bare, &Encryption::Ecdsa).unwrap(), ecdsa);
+    }
+
+    #[test]
+    fn bare_ss58_rejects_prefixed_and_ethereum() {
+        assert!(matches!(
+            parse_bare_ss58(
+                "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+                &Encryption::Sr25519
+            ),
+            Err(DefinitionError::NotBareAddress)
+        ));
+        assert!(matches!(
+            parse_bare_ss58(
+                "KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL",
+                &Encryption::Ethereum
+            ),
+            Err(DefinitionError::NoBareAddressFormat(Encryption::Ethereum))
+        ));
+    }
+
+    /// `//Alice` `Sr25519` address with generic prefix `42`
+    const ALICE_SS58: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    fn sr25519_from_address() {
+        assert_eq!(
+            sr25519_from_ss58(ALICE_SS58).unwrap(),
+            alice(Encryption::Sr25519)
+        );
+        assert!(matches!(
+            sr25519_from_ss58("KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL"),
+            Err(DefinitionError::Ss58(_))
+        ));
+    }
+
+    #[test]
+    fn ethereum_address_is_not_public_key() {
+        let account = hex::decode("e04cc55ebee1cbce552f250e85c57b70b2e2625b").unwrap();
+        assert!(matches!(
+            get_multisigner(&account, &Encryption::Ethereum),
+            Err(DefinitionError::EthereumAddressNotPublicKey)
+        ));
+        assert!(matches!(
+            get_multisigner(&account, &Encryption::Ecdsa),
+            Err(DefinitionError::WrongPublicKeyLength)
+        ));
+        assert!(matches!(
+            get_multisigner(&[1; 32], &Encryption::Ethereum),
+            Err(DefinitionError::WrongPublicKeyLength)
+        ));
+        let public = hex::decode(ALICE_ECDSA).unwrap();
+        assert_eq!(
+            get_multisigner(&public, &Encryption::Ethereum).unwrap(),
+            MultiSigner::Ecdsa(ecdsa::Public::from_raw(public.try_into().unwrap()))
+        );
+    }
+
+    #[test]
+    fn ss58_checksum_checked() {
+        for address in [
+            ALICE_SS58,
+            "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5",
+            "tsYiidXGXmwsjaTrJpA6Z9YuqRipRjVqMrkikExNnpsVoa9cL",
+            "KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL",
+        ] {
+            assert!(ss58_checksum_valid(address), "{address}");
+        }
+        for address in [
+            // corrupted last character
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ",
+            // reserved prefix `46`
+            "5g1axYPrdA2VeKsqmz8WKpa8f3n4T9ou8Qv3f8BaeheH3ApS",
+            // 31-byte key
+            "yA3vprfzKUKan9P1eXE6iMGCMSMDZEnAtb6wEjTEf86ZXt",
+            "0xE04CC55ebEE1cBCE552f250e85c57B70B2E2625b",
+            "",
+        ] {
+            assert!(!ss58_checksum_valid(address), "{address}");
+        }
+    }
+
+    /// EIP-55 Ethereum address of `//Alice` `Ecdsa` key
+    const ALICE_ETH: &str = "0xE04CC55ebEE1cBCE552f250e85c57B70B2E2625b";
+
+    #[test]
+    fn ecdsa_converts_to_ethereum_only() {
+        assert!(can_convert_encryption(
+            Encryption::Ecdsa,
+            Encryption::Ethereum
+        ));
+        assert!(can_convert_encryption(
+            Encryption::Ethereum,
+            Encryption::Ecdsa
+        ));
+        assert!(!can_convert_encryption(
+            Encryption::Sr25519,
+            Encryption::Ed25519
+        ));
+        assert!(!can_convert_encryption(
+            Encryption::Ecdsa,
+            Encryption::Ecdsa
+        ));
+        assert_eq!(
+            ecdsa_to_ethereum_address(&alice(Encryption::Ecdsa)).unwrap(),
+            ALICE_ETH
+        );
+        assert!(matches!(
+            ecdsa_to_ethereum_address(&alice(Encryption::Sr25519)),
+            Err(DefinitionError::EncryptionNotConvertible {
+                from: Encryption::Sr25519,
+                to: Encryption::Ethereum,
+            })
+        ));
+    }
+
+    #[test]
+    fn public_key_forms_for_network() {
+        let sr25519 = alice(Encryption::Sr25519);
+        assert_eq!(
+            public_key_forms(&sr25519, Some(&NetworkSpecs::polkadot())),
+            PublicKeyForms {
+                raw: hex::decode(ALICE_SR25519).unwrap(),
+                hex: ALICE_SR25519.to_string(),
+                ss58: Some(String::from(
+                    "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+                )),
+                eth: None,
+            }
+        );
+        assert_eq!(
+            public_key_forms(&sr25519, None).ss58.unwrap(),
+            "BauKu2iL4fncgfy22YSLGc1aDLpyuUUe5z8yNF2pDtLNr4E"
+        );
+
+        let ethereum_specs = NetworkSpecs {
+            encryption: Encryption::Ethereum,
+            ..NetworkSpecs::westend()
+        };
+        let forms = public_key_forms(&alice(Encryption::Ecdsa), Some(&ethereum_specs));
+        assert_eq!(forms.hex, ALICE_ECDSA);
+        assert_eq!(forms.ss58, None);
+        assert_eq!(forms.eth.unwrap(), ALICE_ETH);
+    }
+
+    #[test]
+    fn eth_address_checksum_policy() {
+        let account = H160::from_slice(&hex::decode(&ALICE_ETH[2..]).unwrap());
+        for policy in [ChecksumPolicy::Require, ChecksumPolicy::Lenient] {
+            assert_eq!(parse_eth_address(ALICE_ETH, policy).unwrap(), account);
+            assert!(matches!(
+                parse_eth_address("0xe04CC55ebEE1cBCE552f250e85c57B70B2E2625b", policy),
+                Err(DefinitionError::EthereumChecksumMismatch)
+            ));
+            for invalid in [
+                &ALICE_ETH[2..],
+                &ALICE_ETH[..41],
+                "0xE04CC55ebEE1cBCE552f250e85c57B70B2E2625g",
+            ] {
+                assert!(matches!(
+                    parse_eth_address(invalid, policy),
+                    Err(DefinitionError::InvalidEthereumAddress)
+                ));
+            }
+        }
+        let lowercase = ALICE_ETH.to_lowercase();
+        let uppercase = format!("0x{}", ALICE_ETH[2..].to_uppercase());
+        for single_case in [&lowercase, &uppercase] {
+            assert_eq!(
+                parse_eth_address(single_case, ChecksumPolicy::Lenient).unwrap(),
+                account
+            );
+            assert!(matches!(
+                parse_eth_address(single_case, ChecksumPolicy::Require),
+                Err(DefinitionError::EthereumChecksumMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn account_id_per_encryption() {
+        assert_eq!(
+            hex::encode(account_id(&alice(Encryption::Sr25519), Encryption::Sr25519).unwrap()),
+            ALICE_SR25519
+        );
+        assert_eq!(
+            hex::encode(account_id(&alice(Encryption::Ecdsa), Encryption::Ecdsa).unwrap()),
+            "01e552298e47454041ea31273b4b630c64c104e4514aa3643490b8aaca9cf8ed"
+        );
+        assert_eq!(
+            hex::encode(account_id(&alice(Encryption::Ethereum), Encryption::Ethereum).unwrap()),
+            ALICE_ETH[2..].to_lowercase()
+        );
+    }
+
+    #[test]
+    fn system_account_storage_key() {
+        let key =
+            account_storage_key(&alice(Encryption::Sr25519), &NetworkSpecs::westend()).unwrap();
+        assert_eq!(
+            hex::encode(key),
+            format!(
+                "26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9de1e86a9a8c739864cf3cc5ec2bea59f{ALICE_SR25519}"
+            )
+        );
+    }
+
+    fn signed_by(
+        uri: &str,
+        encryption: Encryption,
+        message: &[u8],
+    ) -> (MultiSigner, Vec<u8>, MultiSignature) {
+        let (signer, signature) = match encryption {
+            Encryption::Ed25519 => {
+                let pair = ed25519::Pair::from_string(uri, None).unwrap();
+                (
+                    MultiSigner::Ed25519(pair.public()),
+                    MultiSignature::Ed25519(pair.sign(message)),
+                )
+            }
+            Encryption::Sr25519 => {
+                let pair = sr25519::Pair::from_string(uri, None).unwrap();
+                (
+                    MultiSigner::Sr25519(pair.public()),
+                    MultiSignature::Sr25519(pair.sign(message)),
+                )
+            }
+            Encryption::Ecdsa | Encryption::Ethereum => {
+                let pair = ecdsa::Pair::from_string(uri, None).unwrap();
+                (
+                    MultiSigner::Ecdsa(pair.public()),
+                    MultiSignature::Ecdsa(pair.sign(message)),
+                )
+            }
+        };
+        (signer, message.to_vec(), signature)
+    }
+
+    #[test]
+    fn batch_verification_finds_invalid() {
+        let valid = vec![
+            signed_by("//Alice", Encryption::Sr25519, b"first"),
+            signed_by("//Bob", Encryption::Sr25519, b"second"),
+            signed_by("//Alice", Encryption::Ed25519, b"third"),
+            signed_by("//Alice", Encryption::Ecdsa, b"fourth"),
+        ];
+        assert_eq!(verify_batch(&valid), [true; 4]);
+
+        let mut items = valid.clone();
+        items[1].1 = b"tampered".to_vec();
+        let (ed25519_signer, _, _) = signed_by("//Bob", Encryption::Ed25519, b"third");
+        items[2].0 = ed25519_signer;
+        items[3].2 = valid[0].2.clone();
+        assert_eq!(verify_batch(&items), [true, false, false, false]);
+        assert!(verify_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn unhex_prefix_optional() {
+        let bytes = vec![0xde, 0xad, 0xbe, 0xef];
+        for hex_entry in ["deadbeef", "0xdeadbeef", "0XDEADBEEF", "0xDeAdBeEf"] {
+            assert_eq!(unhex(hex_entry).unwrap(), bytes, "{hex_entry}");
+        }
+        for invalid in ["0xdeadbee", "x0deadbeef", "0x0xdeadbeef", "0xdeadbeeg"] {
+            assert!(unhex(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn signature_verified() {
+        for encryption in [Encryption::Ed25519, Encryption::Sr25519, Encryption::Ecdsa] {
+            let (signer, message, signature) = signed_by("//Alice", encryption, b"message");
+            assert!(verify_signature(&signer, &message, &signature));
+            assert!(!verify_signature(&signer, b"other message", &signature));
+            let (bob, _, _) = signed_by("//Bob", encryption, b"message");
+            assert!(!verify_signature(&bob, &message, &signature));
+        }
+        let (sr25519_signer, message, _) = signed_by("//Alice", Encryption::Sr25519, b"message");
+        let (_, _, ed25519_signature) = signed_by("//Alice", Encryption::Ed25519, b"message");
+        assert!(!verify_signature(
+            &sr25519_signer,
+            &message,
+            &ed25519_signature
+        ));
+    }
+
+    #[test]
+    fn ethereum_address_printed_checksummed() {
+        for address in [
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "dbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "D1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let account = H160::from_slice(&hex::decode(address).unwrap());
+            assert_eq!(eip55_checksum(&account), address);
+        }
+        let ethereum = alice(Encryption::Ethereum);
+        for prefix in [Some(42), None] {
+            assert_eq!(
+                print_multisigner_as_base58_or_eth(&ethereum, prefix, Encryption::Ethereum)
+                    .unwrap(),
+                ALICE_ETH
+            );
+        }
+        let not_a_point = MultiSigner::Ecdsa(ecdsa::Public::from_raw([5; 33]));
+        assert!(
+            print_multisigner_as_base58_or_eth(&not_a_point, Some(42), Encryption::Ethereum)
+                .is_err()
+        );
+        assert!(ecdsa_to_ethereum_address(&not_a_point).is_err());
+    }
+
+    #[test]
+    fn bare_ed25519_round_trip() {
+        // `Ed25519` public key of `//Alice`
+        let public =
+            hex::decode("88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee")
+                .unwrap();
+        let ed25519 = get_multisigner(&public, &Encryption::Ed25519).unwrap();
+        let bare = print_multisigner_as_base58_or_eth(&ed25519, None, Encryption::Ed25519).unwrap();
+        assert_eq!(bare, "MTRhi6mgfym7wr3SFG46NxcazneJ7QFaoWfeBMoDNB8UuGY");
+        assert_eq!(
+            parse_bare_ss58(&bare, &Encryption::Ed25519).unwrap(),
+            ed25519
+        );
+        let sr25519_bare = print_multisigner_as_base58_or_eth(
+            &alice(Encryption::Sr25519),
+            None,
+            Encryption::Sr25519,
+        )
+        .unwrap();
+        assert!(matches!(
+            parse_bare_ss58(&sr25519_bare, &Encryption::Ed25519),
+            Err(DefinitionError::NotBareAddress)
+        ));
+    }
+
+    #[test]
+    fn encryption_detected_from_address() {
+        let ecdsa_ss58 = "KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL";
+        assert_eq!(
+            encryption_from_address(ALICE_SS58).unwrap(),
+            Encryption::Sr25519
+        );
+        assert_eq!(
+            encryption_from_address(ecdsa_ss58).unwrap(),
+            Encryption::Ecdsa
+        );
+        assert_eq!(
+            encryption_from_address(ALICE_ETH).unwrap(),
+            Encryption::Ethereum
+        );
+        assert_eq!(
+            encryption_from_address(&ALICE_ETH.to_lowercase()).unwrap(),
+            Encryption::Ethereum
+        );
+        for (hint, expected) in [
+            (Encryption::Ed25519, Encryption::Ed25519),
+            (Encryption::Sr25519, Encryption::Sr25519),
+            (Encryption::Ecdsa, Encryption::Sr25519),
+            (Encryption::Ethereum, Encryption::Sr25519),
+        ] {
+            assert_eq!(
+                encryption_from_address_with_hint(ALICE_SS58, hint).unwrap(),
+                expected
+            );
+            assert_eq!(
+                encryption_from_address_with_hint(ecdsa_ss58, hint).unwrap(),
+                Encryption::Ecdsa
+            );
+        }
+        for invalid in [
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ",
+            "0xE04CC55ebEE1cBCE552f250e85c57B70B2E262",
+            "",
+        ] {
+            assert!(encryption_from_address(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn address_with_account_id() {
+        let westend = NetworkSpecs::westend();
+        assert_eq!(
+            address_and_account_id(&alice(Encryption::Sr25519), &westend).unwrap(),
+            (ALICE_SS58.to_string(), hex::decode(ALICE_SR25519).unwrap())
+        );
+        let ecdsa_westend = NetworkSpecs {
+            encryption: Encryption::Ecdsa,
+            ..NetworkSpecs::westend()
+        };
+        let (address, account) =
+            address_and_account_id(&alice(Encryption::Ecdsa), &ecdsa_westend).unwrap();
+        assert_eq!(address, "KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL");
+        assert_eq!(
+            hex::encode(account),
+            "01e552298e47454041ea31273b4b630c64c104e4514aa3643490b8aaca9cf8ed"
+        );
+        let ethereum = NetworkSpecs {
+            encryption: Encryption::Ethereum,
+            ..NetworkSpecs::westend()
+        };
+        let (address, account) =
+            address_and_account_id(&alice(Encryption::Ethereum), &ethereum).unwrap();
+        assert_eq!(address, ALICE_ETH);
+        assert_eq!(
+            account,
+            account_id(&alice(Encryption::Ethereum), Encryption::Ethereum).unwrap()
+        );
+    }
+
+    #[test]
+    fn prefix_read_from_address() {
+        for (address, prefix) in [
+            (ALICE_SS58, 42),
+            ("15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5", 0),
+            ("HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F", 2),
+            ("tsYiidXGXmwsjaTrJpA6Z9YuqRipRjVqMrkikExNnpsVoa9cL", 2007),
+            ("KW39r9CJjAVzmkf9zQ4YDb2hqfAVGdRqn53eRqyruqpxAP5YL", 42),
+        ] {
+            assert_eq!(prefix_from_address(address).unwrap(), prefix, "{address}");
+        }
+        assert!(matches!(
+            prefix_from_address(ALICE_ETH),
+            Err(DefinitionError::EthereumAddressNoPrefix)
+        ));
+        assert!(prefix_from_address("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ").is_err());
+    }
+
+    #[test]
+    fn address_key_from_multisigner() {
+        let sr25519 = alice(Encryption::Sr25519);
+        let polkadot = NetworkSpecs::polkadot();
+        let address_key = multisigner_to_address_key(&sr25519, Some(polkadot.genesis_hash));
+        assert_eq!(
+            address_key,
+            AddressKey::for_network(sr25519.clone(), &polkadot)
+        );
+        assert_eq!(address_key.multi_signer(), &sr25519);
+        assert_eq!(
+            multisigner_to_address_key(&sr25519, None).key(),
+            [&[1][..], &hex::decode(ALICE_SR25519).unwrap(), &[0]].concat()
+        );
+    }
+
+    #[test]
+    fn identicon_input_is_raw_public_key() {
+        assert_eq!(
+            hex::encode(identicon_input(&alice(Encryption::Sr25519))),
+            ALICE_SR25519
+        );
+        assert_eq!(
+            hex::encode(identicon_input(&alice(Encryption::Ecdsa))),
+            ALICE_ECDSA
+        );
+    }
+}
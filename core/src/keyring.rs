@@ -1,11 +1,13 @@
 use codec::{Decode, Encode};
-use sp_core::H256;
+use sp_core::{H160, H256};
 use sp_runtime::MultiSigner;
+use subtle::ConstantTimeEq;
 
 use crate::{
     crypto::Encryption,
-    error::IdentityResult,
-    helpers::{get_multisigner, unhex},
+    error::{DefinitionError, DefinitionResult, IdentityResult},
+    helpers::{ecdsa_public_to_eth_address, get_multisigner, multisigner_to_encryption, unhex},
+    network_spec::NetworkSpecs,
 };
 
 #[derive(Decode, Encode, Debug, PartialEq, Eq, Clone)]
@@ -26,10 +28,21 @@ impl AddressKey {
         }
     }
 
+    /// Generate [`AddressKey`] of the address in given network, from
+    /// corresponding
+    /// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+    /// value and the network specs.
+    pub fn for_network(multisigner: MultiSigner, specs: &NetworkSpecs) -> Self {
+        Self::new(multisigner, Some(specs.genesis_hash))
+    }
+
     /// Generate [`AddressKey`] from parts: raw public key and [`Encryption`]  
     ///
     /// Could result in error if public key length does not match the
     /// expected length for chosen encryption algorithm.  
+    ///
+    /// For `Ethereum` encryption the input must be the 33-byte compressed
+    /// public key, **not** the 20-byte account address.  
     pub fn from_parts(
         public: &[u8],
         encryption: &Encryption,
@@ -58,6 +71,19 @@ impl AddressKey {
         }
     }
 
+    /// Get Ethereum account address from the [`AddressKey`]
+    ///
+    /// Only `Ecdsa` keys have Ethereum address, other keys result in error.
+    pub fn eth_address(&self) -> DefinitionResult<H160> {
+        match &self.multisigner {
+            MultiSigner::Ecdsa(public) => ecdsa_public_to_eth_address(public),
+            _ => Err(DefinitionError::EncryptionNotConvertible {
+                from: multisigner_to_encryption(&self.multisigner),
+                to: Encryption::Ethereum,
+            }),
+        }
+    }
+
     /// Get [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
     /// from the [`AddressKey`]  
     pub fn multi_signer(&self) -> &MultiSigner {
@@ -65,7 +91,155 @@ impl AddressKey {
     }
 
     /// Transform [`AddressKey`] into `Vec<u8>` database key  
+    ///
+    /// Database key is persistent, so its layout must not change without a
+    /// database migration. Layout is:
+    ///
+    /// - `MultiSigner` variant byte: `00` for `Ed25519`, `01` for `Sr25519`,
+    ///   `02` for `Ecdsa`
+    /// - raw public key, 32 bytes or 33 bytes for `Ecdsa`
+    /// - `00` for root key, or `01` followed by 32-byte genesis hash for
+    ///   network-bound key
+    ///
+    /// For example, `Sr25519` key of `//Alice`, public key
+    /// `d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d`,
+    /// is stored under
+    /// `01d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00`
+    /// as a root key, and under
+    /// `01d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d01`
+    /// followed by Polkadot genesis hash
+    /// `91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3`
+    /// as a Polkadot key.  
     pub fn key(&self) -> Vec<u8> {
         self.encode()
     }
+
+    /// Check if two [`AddressKey`] values are stored under the same database
+    /// key  
+    ///
+    /// Root key (no genesis hash) and network-bound key with the same
+    /// [`MultiSigner`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/enum.MultiSigner.html)
+    /// are always distinct: `None` genesis hash is encoded as a single `0`
+    /// byte, and `Some` as `1` byte followed by the hash.  
+    pub fn is_same_stored_key(&self, other: &AddressKey) -> bool {
+        self.key() == other.key()
+    }
+
+    /// Check if two [`AddressKey`] values are equal, in constant time
+    ///
+    /// Encoded keys are compared, and time depends only on key lengths, not
+    /// on the content. Derived `PartialEq` is fine for lookups and display,
+    /// but comparisons gating security decisions, e.g. secret exposure marks,
+    /// should use this instead.
+    pub fn ct_eq(&self, other: &AddressKey) -> bool {
+        self.key()[..].ct_eq(&other.key()[..]).into()
+    }
+}
+
+/// Transform a set of [`AddressKey`] values into database keys, for bulk
+/// write transaction
+///
+/// Output order matches the input order, and each output key is identical
+/// to the [`AddressKey::key`] output. Buffers are allocated upfront, with
+/// sizes known from the key content.
+pub fn encode_keys(keys: &[AddressKey]) -> Vec<Vec<u8>> {
+    let mut encoded_keys = Vec::with_capacity(keys.len());
+    for address_key in keys {
+        let mut encoded = Vec::with_capacity(address_key.size_hint());
+        address_key.encode_to(&mut encoded);
+        encoded_keys.push(encoded);
+    }
+    encoded_keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Sr25519` public key of `//Alice`
+    const ALICE_SR25519: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    /// `Ecdsa` compressed public key of `//Alice`
+    const ALICE_ECDSA: &str = "020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a1";
+
+    #[test]
+    fn sr25519_key_layout() {
+        let public = hex::decode(ALICE_SR25519).unwrap();
+        let root = AddressKey::from_parts(&public, &Encryption::Sr25519, None).unwrap();
+        assert_eq!(
+            hex::encode(root.key()),
+            "01d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00"
+        );
+        let polkadot =
+            AddressKey::for_network(root.multi_signer().clone(), &NetworkSpecs::polkadot());
+        assert_eq!(
+            hex::encode(polkadot.key()),
+            "01d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d\
+            0191b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3"
+        );
+        assert!(!root.is_same_stored_key(&polkadot));
+        assert_eq!(
+            AddressKey::from_hex(&hex::encode(polkadot.key())).unwrap(),
+            polkadot
+        );
+    }
+
+    #[test]
+    fn ecdsa_key_layout() {
+        let public = hex::decode(ALICE_ECDSA).unwrap();
+        let genesis_hash = Some(NetworkSpecs::polkadot().genesis_hash);
+        let root = AddressKey::from_parts(&public, &Encryption::Ecdsa, None).unwrap();
+        assert_eq!(
+            hex::encode(root.key()),
+            "02020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a100"
+        );
+        let bound = AddressKey::from_parts(&public, &Encryption::Ecdsa, genesis_hash).unwrap();
+        assert_eq!(
+            hex::encode(bound.key()),
+            "02020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a1\
+            0191b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3"
+        );
+        assert_eq!(
+            encode_keys(&[root.clone(), bound.clone()]),
+            [root.key(), bound.key()]
+        );
+        assert!(root.ct_eq(&root.clone()));
+        assert!(!root.ct_eq(&bound));
+    }
+
+    #[test]
+    fn address_key_for_network() {
+        let public = hex::decode(ALICE_SR25519).unwrap();
+        let multisigner = get_multisigner(&public, &Encryption::Sr25519).unwrap();
+        for specs in [NetworkSpecs::kusama(), NetworkSpecs::westend()] {
+            let address_key = AddressKey::for_network(multisigner.clone(), &specs);
+            assert_eq!(
+                address_key,
+                AddressKey::new(multisigner.clone(), Some(specs.genesis_hash))
+            );
+            assert_eq!(
+                address_key.key()[33..],
+                [&[1][..], specs.genesis_hash.as_bytes()].concat()
+            );
+        }
+    }
+
+    #[test]
+    fn eth_address_of_ecdsa_key() {
+        let public = hex::decode(ALICE_ECDSA).unwrap();
+        let address_key = AddressKey::from_parts(&public, &Encryption::Ecdsa, None).unwrap();
+        assert_eq!(
+            hex::encode(address_key.eth_address().unwrap()),
+            "e04cc55ebee1cbce552f250e85c57b70b2e2625b"
+        );
+        let public = hex::decode(ALICE_SR25519).unwrap();
+        let address_key = AddressKey::from_parts(&public, &Encryption::Sr25519, None).unwrap();
+        assert!(matches!(
+            address_key.eth_address(),
+            Err(DefinitionError::EncryptionNotConvertible {
+                from: Encryption::Sr25519,
+                to: Encryption::Ethereum,
+            })
+        ));
+    }
 }
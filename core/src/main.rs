@@ -1,105 +1,996 @@
+mod clock;
 mod crypto;
 mod definitions;
 mod error;
 mod helpers;
 mod keyring;
+mod manifest;
 mod network_spec;
+mod payload;
+mod qr;
 mod users;
 
-use bip39::{Language, Mnemonic, MnemonicType};
-use definitions::{Encryption, IdentityRecord};
-use error::{IdentityError, IdentityResult};
-use helpers::multisigner_to_public;
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
+use codec::Decode;
+use crypto::Encryption;
+use definitions::IdentityRecord;
+use error::{DefinitionError, DefinitionResult, IdentityError, IdentityResult};
+use helpers::{eth_message_hash, multisigner_to_public};
 use keyring::AddressKey;
 use lazy_static::lazy_static;
 use network_spec::{NetworkSpecs, NetworkSpecsKey};
 use regex::Regex;
-use sp_core::{crypto::Zeroize, ecdsa, ed25519, sr25519, Pair};
-use sp_runtime::MultiSigner;
-use users::AddressDetails;
+use sp_core::{
+    crypto::{DeriveJunction, ExposeSecret, SecretString, SecretStringError, Zeroize},
+    ecdsa, ed25519,
+    hashing::{blake2_256, keccak_256, sha2_256},
+    sr25519, Pair, H256,
+};
+use sp_runtime::{MultiSignature, MultiSigner};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+use tiny_hderive::bip32::ExtendedPrivKey;
+use unicode_normalization::UnicodeNormalization;
+use users::{seed_secret_exposed, AddressDetails};
 
 use crate::network_spec::default_network_specs;
 
 lazy_static! {
     static ref REG_PATH: Regex =
         Regex::new(r"^(?P<path>(//?[^/]+)*)(///(?P<password>.+))?$").expect("known value");
+    static ref REG_BIP44: Regex = Regex::new(r"^m(/[0-9]+'?)+$").expect("known value");
 }
 
-enum CreateAddressPayload {
+enum CreateAddressPayload<'a> {
     SocialProvider,
-    EmailAndPassword,
+    EmailAndPassword {
+        email: &'a str,
+        password: &'a str,
+        derivation_path: &'a str,
+        seed_name: &'a str,
+    },
     SeedPhrase {
-        derivation_path: &'static str,
-        seed_phrase: String,
-        seed_name: &'static str,
+        derivation_path: &'a str,
+        seed_phrase: &'a str,
+        seed_name: &'a str,
+        /// Optional BIP39 passphrase, the "25th word"
+        passphrase: Option<String>,
+    },
+}
+
+/// Result of address creation
+///
+/// [`IdentityRecord`] is produced exactly when the address is created within
+/// a network.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AddressOutcome {
+    /// Address bound to a network
+    WithNetwork {
+        details: AddressDetails,
+        record: IdentityRecord,
     },
+
+    /// Root address, not bound to any network
+    NetworkAgnostic { details: AddressDetails },
+}
+
+impl AddressOutcome {
+    /// Get [`AddressDetails`] of the created address
+    pub fn details(&self) -> &AddressDetails {
+        match self {
+            AddressOutcome::WithNetwork { details, .. } => details,
+            AddressOutcome::NetworkAgnostic { details } => details,
+        }
+    }
+
+    /// Split into [`AddressDetails`] and [`IdentityRecord`], if any
+    pub fn into_parts(self) -> (AddressDetails, Option<IdentityRecord>) {
+        match self {
+            AddressOutcome::WithNetwork { details, record } => (details, Some(record)),
+            AddressOutcome::NetworkAgnostic { details } => (details, None),
+        }
+    }
+}
+
+/// Seed phrase strength, expressed as number of words
+///
+/// Only valid BIP39 phrase lengths could be expressed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PhraseStrength {
+    Words12,
+    Words15,
+    Words18,
+    Words21,
+    Words24,
+}
+
+impl PhraseStrength {
+    fn mnemonic_type(&self) -> MnemonicType {
+        match self {
+            PhraseStrength::Words12 => MnemonicType::Words12,
+            PhraseStrength::Words15 => MnemonicType::Words15,
+            PhraseStrength::Words18 => MnemonicType::Words18,
+            PhraseStrength::Words21 => MnemonicType::Words21,
+            PhraseStrength::Words24 => MnemonicType::Words24,
+        }
+    }
+}
+
+impl TryFrom<u32> for PhraseStrength {
+    type Error = IdentityError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            12 => Ok(PhraseStrength::Words12),
+            15 => Ok(PhraseStrength::Words15),
+            18 => Ok(PhraseStrength::Words18),
+            21 => Ok(PhraseStrength::Words21),
+            24 => Ok(PhraseStrength::Words24),
+            _ => Err(IdentityError::InvalidWordCount(value)),
+        }
+    }
+}
+
+impl FromStr for PhraseStrength {
+    type Err = IdentityError;
+
+    /// Parse number of words, e.g. `"24"`; non-numeric input is reported
+    /// as zero words
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words_number: u32 = s
+            .trim()
+            .parse()
+            .map_err(|_| IdentityError::InvalidWordCount(0))?;
+        Self::try_from(words_number)
+    }
+}
+
+/// Generate random phrase of given [`PhraseStrength`].
+///
+/// The output is a **secret seed phrase**, returned as [`SecretPhrase`], and
+/// is zeroized once dropped.
+pub fn generate_phrase(strength: PhraseStrength) -> SecretPhrase {
+    let mnemonic = Mnemonic::new(strength.mnemonic_type(), Language::English);
+    SecretPhrase(mnemonic.into_phrase())
+}
+
+/// Seed phrase that is zeroized on drop
+///
+/// Derefs to `&str`, so it could be passed wherever the phrase is borrowed.
+/// Cloning or `to_string` would make copies that are not zeroized, and
+/// should be avoided; [`SecretString`] takes the phrase over without a copy.
+pub struct SecretPhrase(String);
+
+impl Zeroize for SecretPhrase {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretPhrase {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl std::ops::Deref for SecretPhrase {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<SecretPhrase> for SecretString {
+    fn from(mut phrase: SecretPhrase) -> Self {
+        SecretString::new(std::mem::take(&mut phrase.0))
+    }
 }
 
 /// Generate random phrase with given number of words.
 ///
-/// The output is a **secret seed phrase**.
+/// Same as [`generate_phrase`], but for unchecked user input: number of words
+/// other than 12, 15, 18, 21, or 24 results in
+/// [`IdentityError::InvalidWordCount`] instead of panic.
+///
+/// Phrase is returned as [`SecretPhrase`], and is zeroized once dropped.
+pub fn generate_random_phrase(words_number: u32) -> IdentityResult<SecretPhrase> {
+    generate_random_phrase_in(words_number, Language::English)
+}
+
+/// Generate random phrase with given number of words, from the BIP39
+/// wordlist of `language`
+///
+/// Keys are derived from English phrases only, so non-English phrase must
+/// go through [`to_english_phrase`] before address creation. Both phrases
+/// have same entropy and give same Substrate keys, but not same BIP32 keys,
+/// see [`to_english_phrase`].
+pub fn generate_random_phrase_in(
+    words_number: u32,
+    language: Language,
+) -> IdentityResult<SecretPhrase> {
+    let strength = PhraseStrength::try_from(words_number)?;
+    Ok(SecretPhrase(
+        Mnemonic::new(strength.mnemonic_type(), language).into_phrase(),
+    ))
+}
+
+/// Generate random phrase with given number of words, together with the
+/// index of each word in the BIP39 English wordlist.
+///
+/// Indices are in phrase order and are in `0..2048` range, so that the device
+/// could render words with their positions and verify them against indices.
+///
+/// The output is a **secret seed phrase**, returned as [`SecretPhrase`], and
+/// the indices are exactly as sensitive as the phrase itself: phrase could be
+/// restored from them. Caller is expected to zeroize the indices once they
+/// are not needed anymore.
+pub fn generate_phrase_with_indices(words_number: u32) -> IdentityResult<(SecretPhrase, Vec<u16>)> {
+    let strength = PhraseStrength::try_from(words_number)?;
+    let mnemonic = Mnemonic::new(strength.mnemonic_type(), Language::English);
+    let indices = word_indices(mnemonic.entropy(), words_number as usize);
+    Ok((SecretPhrase(mnemonic.into_phrase()), indices))
+}
+
+/// Bring pasted seed phrase into canonical form: lowercase words separated
+/// by single spaces, without leading or trailing whitespace
+///
+/// Phrase copied from a formatted backup could contain newlines, tabs,
+/// repeated spaces or capitalized words; such phrase would fail validation,
+/// or, worse, derive a different seed.
+///
+/// The output, as well as the input, is a **secret seed phrase**. Neither is
+/// logged here, and the caller must not log them either.
+pub fn normalize_phrase(input: &str) -> String {
+    // built in place, so that no intermediate copies of the words are left
+    // behind in memory
+    let mut phrase = String::with_capacity(input.len());
+    for word in input.split_whitespace() {
+        if !phrase.is_empty() {
+            phrase.push(' ');
+        }
+        phrase.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    phrase
+}
+
+/// Check that the user-provided seed phrase is a valid English BIP39
+/// mnemonic, before any derivation happens.
+///
+/// Words must be from the wordlist, their number must be 12, 15, 18, 21, or
+/// 24, and the last word must carry correct checksum; otherwise
+/// [`IdentityError::InvalidMnemonic`] is returned.
+pub fn validate_seed_phrase(phrase: &str) -> IdentityResult<()> {
+    validate_seed_phrase_in(phrase, Language::English)
+}
+
+/// Check that the seed phrase is a valid BIP39 mnemonic in `language`
+///
+/// Same checks as in [`validate_seed_phrase`], against the `language`
+/// wordlist. Pasted phrase, e.g. Japanese one with ideographic spaces,
+/// should go through [`normalize_phrase`] first.
+pub fn validate_seed_phrase_in(phrase: &str, language: Language) -> IdentityResult<()> {
+    Mnemonic::validate(phrase, language).map_err(|_| IdentityError::InvalidMnemonic)
+}
+
+/// Get English seed phrase for raw entropy, e.g. provided by hardware RNG
+///
+/// Entropy must be 16, 20, 24, 28 or 32 bytes long, for 12 to 24 words.
+/// Both the entropy and the output are secret, the output is returned as
+/// [`SecretPhrase`].
+pub fn phrase_from_entropy(entropy: &[u8]) -> IdentityResult<SecretPhrase> {
+    if !matches!(entropy.len(), 16 | 20 | 24 | 28 | 32) {
+        return Err(IdentityError::InvalidEntropyLength(entropy.len()));
+    }
+    let mnemonic = Mnemonic::from_entropy(entropy, Language::English)
+        .map_err(|_| IdentityError::InvalidEntropyLength(entropy.len()))?;
+    Ok(SecretPhrase(mnemonic.into_phrase()))
+}
+
+/// Get raw entropy of English seed phrase, inverse of
+/// [`phrase_from_entropy`]
+///
+/// Invalid phrase results in [`IdentityError::InvalidMnemonic`].
+pub fn entropy_from_phrase(phrase: &str) -> IdentityResult<Vec<u8>> {
+    let mnemonic = Mnemonic::from_phrase(phrase, Language::English)
+        .map_err(|_| IdentityError::InvalidMnemonic)?;
+    Ok(mnemonic.entropy().to_vec())
+}
+
+/// Get English seed phrase with the same entropy as `phrase` in `language`
+///
+/// Substrate derives keys from the mnemonic entropy, not from the words, so
+/// phrase in any BIP39 language and its English counterpart produce the same
+/// `Ed25519`, `Sr25519` and `Ecdsa` keys, and same `Ethereum` keys with
+/// Substrate junctions. Substrate parses English phrases only, so
+/// non-English phrases must be converted before address creation.
+///
+/// BIP32 `Ethereum` paths, e.g. `m/44'/60'/0'/0/0`, are different: BIP39
+/// seed is PBKDF2 over the words themselves, so the English phrase gives
+/// other keys than the original phrase gives in wallets that accept it as
+/// is, e.g. MetaMask accounts of the phrase are not recovered this way.
+///
+/// Phrase goes through [`normalize_phrase`] first, so words could be
+/// separated by any whitespace, including ideographic space `U+3000` used in
+/// Japanese phrases. Both input and output are secret, the output is
+/// returned as [`SecretPhrase`].
+pub fn to_english_phrase(phrase: &str, language: Language) -> IdentityResult<SecretPhrase> {
+    let mut normalized = normalize_phrase(phrase);
+    let mnemonic = Mnemonic::from_phrase(&normalized, language);
+    normalized.zeroize();
+    let mnemonic = mnemonic.map_err(|_| IdentityError::InvalidMnemonic)?;
+    let english = Mnemonic::from_entropy(mnemonic.entropy(), Language::English)
+        .expect("entropy of valid mnemonic is valid in any language");
+    Ok(SecretPhrase(english.into_phrase()))
+}
+
+/// Count distinct words that appear in the phrase more than once.
+///
+/// BIP39 does permit repeated words, so this is only a heuristic for the
+/// "unusual phrase" warning and must never block the import of a valid
+/// phrase.
+///
+/// Phrases are at most 24 words long, so the quadratic scan is cheaper than
+/// building a set and does not allocate.
+pub fn repeated_word_count(phrase: &str) -> usize {
+    let words = || phrase.split_whitespace();
+    words()
+        .enumerate()
+        .filter(|(i, word)| {
+            !words().take(*i).any(|w| w == *word) && words().skip(i + 1).any(|w| w == *word)
+        })
+        .count()
+}
+
+/// Complete BIP39 word from its prefix.
+///
+/// English words are uniquely identified by their first four letters, so
+/// any prefix of four or more letters of an English word completes to a
+/// single word. Other wordlists give no such guarantee, and a prefix could
+/// stay ambiguous at any length. Complete word is completed to itself, even
+/// if it is a prefix of other words, e.g. `act`. Prefix matching several
+/// words, or no words at all, is an error.
+///
+/// Wordlist is scanned linearly: only the English wordlist is sorted in
+/// byte order, so that prefix search would not work for the others.
+pub fn complete_word(prefix: &str, language: Language) -> IdentityResult<&'static str> {
+    let words: Vec<&'static str> = language
+        .wordlist()
+        .get_words_by_prefix("")
+        .iter()
+        .copied()
+        .filter(|word| word.starts_with(prefix))
+        .collect();
+    if let Some(&word) = words.iter().find(|&&word| word == prefix) {
+        return Ok(word);
+    }
+    match words[..] {
+        [word] => Ok(word),
+        [] => Err(IdentityError::UnknownWordPrefix(prefix.to_string())),
+        _ => Err(IdentityError::AmbiguousWordPrefix(prefix.to_string())),
+    }
+}
+
+/// Split mnemonic entropy with appended checksum into 11-bit wordlist indices
+///
+/// Checksum is the first `entropy_bits / 32` bits of the entropy `sha256`
+/// hash, it never exceeds one byte for valid entropy lengths.
+fn word_indices(entropy: &[u8], words_number: usize) -> Vec<u16> {
+    let mut bits = entropy.to_vec();
+    bits.push(sha2_256(entropy)[0]);
+    let indices = (0..words_number)
+        .map(|word| {
+            (word * 11..(word + 1) * 11).fold(0u16, |index, bit| {
+                (index << 1) | ((bits[bit / 8] >> (7 - bit % 8)) & 1) as u16
+            })
+        })
+        .collect();
+    bits.zeroize();
+    indices
+}
+
+/// Run `f` with the seed phrase, and zeroize the phrase afterwards.
+///
+/// All address creation flows should borrow the phrase from a single
+/// [`SecretString`] this way, instead of making `String` copies of it, that
+/// would not be zeroized.
+pub fn with_seed<F, R>(phrase: SecretString, f: F) -> R
+where
+    F: FnOnce(&str) -> R,
+{
+    // `SecretString` is zeroized on drop
+    f(phrase.expose_secret().as_str())
+}
+
+/// Suggest seed name for a seed imported without a name.
+///
+/// Suggestion is the first `Account N`, starting from `Account 1`, that is
+/// not in `existing` seed names.
+pub fn suggest_seed_name(existing: &[String]) -> String {
+    (1..)
+        .map(|n| format!("Account {n}"))
+        .find(|name| !existing.contains(name))
+        .expect("existing seed names are finite")
+}
+
+/// Maximal length of the derivation password, in characters
+///
+/// Password is mixed into the key derivation, and overly long password is
+/// only a memory and time burden for a constrained device.
+const MAX_PASSWORD_LEN: usize = 256;
+
+/// Check that the password in `REG_PATH` captures, if any, is not longer
+/// than [`MAX_PASSWORD_LEN`]
+///
+/// Password is checked in place: captured password borrows from the path,
+/// and is zeroized together with it.
+fn check_password_len(caps: &regex::Captures) -> IdentityResult<()> {
+    match caps.name("password") {
+        Some(password) if password.as_str().chars().count() > MAX_PASSWORD_LEN => {
+            Err(IdentityError::PasswordTooLong(MAX_PASSWORD_LEN))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Single junction of the derivation path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Junction {
+    /// Soft junction, `/name`
+    Soft(String),
+
+    /// Hard junction, `//name`
+    Hard(String),
+}
+
+/// Derivation path structure, for display before the address is created
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationInfo {
+    /// path with soft (`/`) and hard (`//`) junctions only, without password
+    pub cropped_path: String,
+
+    /// whether the path has a password, UI should warn about it
+    pub has_pwd: bool,
+
+    /// junctions in path order
+    pub junctions: Vec<Junction>,
+}
+
+/// Parse derivation path, in the format `REG_PATH` handles
+///
+/// Password itself is neither returned nor copied, only its presence is
+/// reported. Path that `REG_PATH` rejects, e.g. with an empty junction,
+/// results in [`IdentityError::InvalidDerivationPath`].
+pub fn parse_derivation_path(path: &str) -> IdentityResult<DerivationInfo> {
+    let caps = REG_PATH
+        .captures(path)
+        .ok_or(IdentityError::InvalidDerivationPath)?;
+    check_password_len(&caps)?;
+    let cropped_path = caps.name("path").map_or("", |a| a.as_str());
+
+    // `//a/b` splits into `["", "", "a", "b"]`, with empty part before each
+    // junction name of hard junction
+    let mut junctions = Vec::new();
+    let mut hard = false;
+    for part in cropped_path.split('/').skip(1) {
+        if part.is_empty() {
+            hard = true;
+        } else if hard {
+            junctions.push(Junction::Hard(part.to_string()));
+            hard = false;
+        } else {
+            junctions.push(Junction::Soft(part.to_string()));
+        }
+    }
+    Ok(DerivationInfo {
+        cropped_path: cropped_path.to_string(),
+        has_pwd: caps.name("password").is_some(),
+        junctions,
+    })
+}
+
+/// Get next free hard `//N` account index for each seed found in `addresses`
+///
+/// Index is one above the largest numeric hard junction ending an address
+/// path of the seed, e.g. `//polkadot//3` or `//3`, so that new account never
+/// reuses an index, even if there are gaps. Seeds with no such address start
+/// from `0`. Seeds with an address ending in `//4294967295` have no free
+/// index above it, and get `None`.
+pub fn next_indices_per_seed(addresses: &[AddressDetails]) -> BTreeMap<String, Option<u32>> {
+    let mut next_indices = BTreeMap::new();
+    for address_details in addresses {
+        let next_index = next_indices
+            .entry(address_details.seed_name.to_string())
+            .or_insert(Some(0));
+        let last_index = parse_derivation_path(&address_details.path)
+            .ok()
+            .and_then(|info| match info.junctions.last() {
+                Some(Junction::Hard(name)) => name.parse::<u32>().ok(),
+                _ => None,
+            });
+        if let (Some(index), Some(next)) = (last_index, *next_index) {
+            *next_index = index.checked_add(1).map(|above| next.max(above));
+        }
+    }
+    next_indices
+}
+
+/// Check if derivation path has an empty junction, e.g. `//`, `/` or `//a//`
+///
+/// Substrate would derive empty junction from empty byte string, but it is
+/// almost always a typo, and `REG_PATH` does not capture it, so the stored
+/// cropped path would not match the derivation actually used.
+///
+/// Since `///` separates the password, empty junction could only be the
+/// trailing `/` of the part before the password.
+fn has_empty_junction(derivation_path: &str) -> bool {
+    derivation_path
+        .split("///")
+        .next()
+        .is_some_and(|junctions| junctions.ends_with('/'))
+}
+
+/// Check if derivation path has soft (`/`) junctions
+///
+/// Soft derivation allows public derivation: leaked parent public key
+/// together with any child secret exposes the parent and all the siblings.
+/// Account summary should flag accounts with soft junctions, if they hold
+/// significant funds.
+///
+/// Password part (after `///`) is not a junction and is not checked.
+pub fn has_soft_junctions(path: &str) -> bool {
+    let mut rest = path.split("///").next().unwrap_or_default();
+    while let Some(position) = rest.find('/') {
+        match rest[position..].strip_prefix("//") {
+            Some(after_hard) => rest = after_hard,
+            None => return true,
+        }
+    }
+    false
+}
+
+/// Rough estimate of time, in milliseconds, needed to derive `count`
+/// addresses with given [`Encryption`].
+///
+/// Used to decide whether to display progress or move derivation into
+/// background. Per-derivation cost is dominated by seed phrase to seed
+/// conversion, see [`derivation_cost_us`].
+pub fn estimate_derivation_ms(count: usize, encryption: Encryption) -> u64 {
+    (count as u64)
+        .saturating_mul(derivation_cost_us(encryption))
+        .div_ceil(1_000)
+}
+
+/// Cost of single address derivation from seed phrase, in microseconds
+///
+/// The figures are rough estimates for embedded-class hardware and are
+/// **not measured yet**. They are to be calibrated with the ignored
+/// `derivation_cost_measured` test, run in release mode on the target
+/// device:
+///
+/// `cargo test --release -- --ignored derivation_cost_measured --nocapture`
+///
+/// and replaced with its output, together with the device it was run on.
+fn derivation_cost_us(encryption: Encryption) -> u64 {
+    match encryption {
+        Encryption::Ed25519 => 4_000,
+        Encryption::Sr25519 => 5_000,
+        Encryption::Ecdsa | Encryption::Ethereum => 6_000,
+    }
+}
+
+/// Recover the single missing word of a seed phrase.
+///
+/// `partial` is the phrase without the missing word, `missing_index` is the
+/// position of the missing word in the complete phrase. All 2048 English
+/// words are tried on that position; candidates with valid checksum are
+/// derived with `path` and `encryption`, and the word producing
+/// `expected_address` is returned. Candidate phrases are zeroized after use.
+///
+/// Only one word could be missing: `partial` must be one word shorter than
+/// valid phrase length.
+pub fn recover_missing_word(
+    partial: &[&str],
+    missing_index: usize,
+    expected_address: &MultiSigner,
+    path: &str,
+    encryption: Encryption,
+) -> IdentityResult<Option<String>> {
+    let words_number = partial.len() + 1;
+    if MnemonicType::for_word_count(words_number).is_err() {
+        return Err(IdentityError::InvalidWordCount(words_number as u32));
+    }
+    if missing_index > partial.len() {
+        return Err(IdentityError::MissingWordIndex(missing_index));
+    }
+    for &candidate in Language::English.wordlist().get_words_by_prefix("") {
+        let mut words = partial.to_vec();
+        words.insert(missing_index, candidate);
+        let mut phrase = words.join(" ");
+        let matches = match Mnemonic::validate(&phrase, Language::English) {
+            Ok(()) => full_address_to_multisigner(&phrase, path, None, encryption)
+                .map(|multisigner| multisigner == *expected_address),
+            Err(_) => Ok(false),
+        };
+        phrase.zeroize();
+        if matches? {
+            return Ok(Some(candidate.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Check if derivation path is BIP32 path, e.g. BIP44 `m/44'/60'/0'/0/0`
+///
+/// Such paths are used for `Ethereum` addresses, to match the addresses of
+/// MetaMask and other Ethereum wallets with the same seed phrase. Hardened
+/// indices are marked with `'`.
+///
+/// E.g. `test test test test test test test test test test test junk` with
+/// `m/44'/60'/0'/0/0` gives `0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266`,
+/// the first MetaMask and Hardhat account of this phrase.
+pub fn is_bip44_path(derivation_path: &str) -> bool {
+    REG_BIP44.is_match(derivation_path)
+}
+
+/// Derive secp256k1 key pair with BIP32 path from seed phrase
 ///
-/// Its zeroization and safe handling are delegated to hardware.
-pub fn generate_random_phrase(words_number: u32) -> IdentityResult<String> {
-    let mnemonic_type = MnemonicType::for_word_count(words_number as usize).unwrap();
-    let mnemonic = Mnemonic::new(mnemonic_type, Language::English);
-    Ok(mnemonic.into_phrase())
+/// Unlike Substrate, BIP32 runs PBKDF2 over the phrase words, with
+/// `"mnemonic" + passphrase` salt, and derives the keys from the resulting
+/// 64-byte seed. Phrase must be English, as in the rest of the crate.
+fn bip44_pair(
+    seed_phrase: &str,
+    derivation_path: &str,
+    passphrase: Option<&str>,
+) -> IdentityResult<ecdsa::Pair> {
+    let mnemonic = Mnemonic::from_phrase(seed_phrase, Language::English)
+        .map_err(|_| IdentityError::InvalidMnemonic)?;
+    let seed = Seed::new(&mnemonic, passphrase.unwrap_or(""));
+    let mut secret = ExtendedPrivKey::derive(seed.as_bytes(), derivation_path)
+        .map_err(|_| IdentityError::InvalidDerivationPath)?
+        .secret();
+    let pair = ecdsa::Pair::from_seed_slice(&secret).map_err(IdentityError::SecretStringError);
+    secret.zeroize();
+    pair
 }
 
 /// Get public key from seed phrase and derivation path
+///
+/// BIP39 `passphrase`, if any, goes into the seed derivation as PBKDF2 salt
+/// `"mnemonic" + passphrase`, before any junctions are applied. Substrate
+/// runs PBKDF2 over the mnemonic entropy, same as `subkey` and
+/// `polkadot{.js}`, so the resulting keys match the keys these tools derive
+/// with the same passphrase given as `///password`. Passphrase replaces the
+/// password in `derivation_path`, if both are present.
+///
+/// For `Ethereum`, BIP32 `derivation_path` (see [`is_bip44_path`]) is
+/// derived as BIP32 rather than with Substrate junctions, so that
+/// `m/44'/60'/0'/0/0` gives the first MetaMask account of the phrase.
 fn full_address_to_multisigner(
-    mut full_address: String,
+    seed_phrase: &str,
+    derivation_path: &str,
+    passphrase: Option<String>,
     encryption: Encryption,
 ) -> Result<MultiSigner, IdentityError> {
+    let password_override = passphrase.as_deref();
+    let mut full_address = String::with_capacity(seed_phrase.len() + derivation_path.len());
+    full_address.push_str(seed_phrase);
+    full_address.push_str(derivation_path);
+    // Timing of this match reveals the encryption, and it is left as is on
+    // purpose. Encryption is not secret: it is stored in `AddressDetails`
+    // and `NetworkSpecsKey` in plain, and every signature discloses it by its
+    // variant. Making the schemes indistinguishable by timing would mean
+    // padding each derivation to the slowest one, for no gain. What must not
+    // leak through timing is the secret, and that is up to each scheme
+    // implementation: its cost depends on the seed phrase and junctions
+    // length, not on their content.
     let multisigner_result = match encryption {
-        Encryption::Ed25519 => match ed25519::Pair::from_string(&full_address, None) {
+        Encryption::Ed25519 => match ed25519::Pair::from_string(&full_address, password_override) {
             Ok(a) => Ok(MultiSigner::Ed25519(a.public())),
             Err(e) => Err(IdentityError::SecretStringError(e)),
         },
-        Encryption::Sr25519 => match sr25519::Pair::from_string(&full_address, None) {
+        Encryption::Sr25519 => match sr25519::Pair::from_string(&full_address, password_override) {
             Ok(a) => Ok(MultiSigner::Sr25519(a.public())),
             Err(e) => Err(IdentityError::SecretStringError(e)),
         },
+        Encryption::Ethereum if is_bip44_path(derivation_path) => {
+            bip44_pair(seed_phrase, derivation_path, password_override)
+                .map(|a| MultiSigner::Ecdsa(a.public()))
+        }
         Encryption::Ecdsa | Encryption::Ethereum => {
-            match ecdsa::Pair::from_string(&full_address, None) {
+            match ecdsa::Pair::from_string(&full_address, password_override) {
                 Ok(a) => Ok(MultiSigner::Ecdsa(a.public())),
                 Err(e) => Err(IdentityError::SecretStringError(e)),
             }
         }
     };
     full_address.zeroize();
+    if let Some(mut passphrase) = passphrase {
+        passphrase.zeroize();
+    }
     multisigner_result
 }
 
+/// Sign `message` with the key derived from seed phrase and derivation path
+///
+/// Phrase and derivation are NFKD-normalized, same as in address creation,
+/// so that the signing key matches the created address. Signature variant
+/// matches the [`Encryption`]. `Ecdsa` signs `blake2_256` message hash, as
+/// Substrate does. `Ethereum` produces `Ecdsa` signature too, but over
+/// `keccak_256` message hash, as Ethereum-compatible chains, e.g. Moonbeam,
+/// check it.
+///
+/// Empty `derivation_path` signs with the root key, derived from the seed
+/// phrase alone, i.e. the key of the network-agnostic address created with
+/// empty path. Path with empty junction is rejected, same as in address
+/// creation, so that `//` could not silently sign with some other key.
+///
+/// `passphrase` is the BIP39 passphrase the address was created with, if
+/// any, and is used the same way as in address creation, see
+/// [`CreateAddressPayload::SeedPhrase`].
+///
+/// Combined secret string and normalized passphrase are zeroized after the
+/// key is derived, regardless of the result.
+pub fn sign_message(
+    seed_phrase: &str,
+    derivation_path: &str,
+    passphrase: Option<&str>,
+    encryption: Encryption,
+    message: &[u8],
+) -> IdentityResult<MultiSignature> {
+    if seed_phrase.is_empty() {
+        return Err(IdentityError::EmptySeed);
+    }
+    if has_empty_junction(derivation_path) {
+        return Err(IdentityError::EmptyJunction);
+    }
+    let mut normalized_passphrase: Option<String> =
+        passphrase.map(|passphrase| passphrase.nfkd().collect());
+    let password_override = normalized_passphrase.as_deref();
+    let signature_result = if encryption == Encryption::Ethereum && is_bip44_path(derivation_path) {
+        let mut normalized_phrase: String = seed_phrase.nfkd().collect();
+        let pair_result = bip44_pair(&normalized_phrase, derivation_path, password_override);
+        normalized_phrase.zeroize();
+        pair_result.map(|pair| MultiSignature::Ecdsa(pair.sign_prehashed(&keccak_256(message))))
+    } else {
+        let mut full_address: String = seed_phrase.nfkd().chain(derivation_path.nfkd()).collect();
+        let signature_result = match encryption {
+            Encryption::Ed25519 => ed25519::Pair::from_string(&full_address, password_override)
+                .map(|pair| MultiSignature::Ed25519(pair.sign(message))),
+            Encryption::Sr25519 => sr25519::Pair::from_string(&full_address, password_override)
+                .map(|pair| MultiSignature::Sr25519(pair.sign(message))),
+            Encryption::Ecdsa => ecdsa::Pair::from_string(&full_address, password_override)
+                .map(|pair| MultiSignature::Ecdsa(pair.sign(message))),
+            Encryption::Ethereum => ecdsa::Pair::from_string(&full_address, password_override)
+                .map(|pair| MultiSignature::Ecdsa(pair.sign_prehashed(&keccak_256(message)))),
+        };
+        full_address.zeroize();
+        signature_result.map_err(IdentityError::SecretStringError)
+    };
+    if let Some(passphrase) = normalized_passphrase.as_mut() {
+        passphrase.zeroize();
+    }
+    signature_result
+}
+
+/// Sign `message` with the root key of the seed phrase, e.g. to prove the
+/// seed ownership
+///
+/// Same as [`sign_message`] with empty derivation path.
+pub fn sign_with_root_key(
+    seed_phrase: &str,
+    passphrase: Option<&str>,
+    encryption: Encryption,
+    message: &[u8],
+) -> IdentityResult<MultiSignature> {
+    sign_message(seed_phrase, "", passphrase, encryption, message)
+}
+
+/// Sign dApp authentication challenge
+///
+/// Signed message is `<Bytes>{domain}:{hex(nonce)}</Bytes>`. Domain
+/// separation prevents the signature produced for one dApp from being
+/// replayed to another, and `<Bytes>` wrapping makes the message impossible
+/// to interpret as a transaction.
+pub fn sign_auth_challenge(
+    seed_phrase: &str,
+    path: &str,
+    passphrase: Option<&str>,
+    encryption: Encryption,
+    domain: &str,
+    nonce: &[u8],
+) -> IdentityResult<MultiSignature> {
+    let message = format!("{domain}:{}", hex::encode(nonce));
+    sign(
+        ToSign::PlainMessage(message),
+        seed_phrase,
+        path,
+        passphrase,
+        encryption,
+    )
+}
+
+/// Sign 32-byte digest directly, without hashing it again
+///
+/// **Dangerous if misused**: the digest is signed blindly, there is no way to
+/// check what was hashed into it, it could as well be a transaction hash.
+/// Use only for flows where the hashing is deliberately done elsewhere.
+///
+/// Only `Ecdsa` and `Ethereum` sign the message hash, and support this;
+/// `Ed25519` and `Sr25519` sign the message itself, and result in error.
+///
+/// `passphrase` is used same as in [`sign_message`].
+pub fn sign_prehashed(
+    seed_phrase: &str,
+    path: &str,
+    passphrase: Option<&str>,
+    encryption: Encryption,
+    digest: &[u8; 32],
+) -> IdentityResult<MultiSignature> {
+    if matches!(encryption, Encryption::Ed25519 | Encryption::Sr25519) {
+        return Err(IdentityError::PrehashedSigningNotSupported(encryption));
+    }
+    let mut normalized_passphrase: Option<String> =
+        passphrase.map(|passphrase| passphrase.nfkd().collect());
+    let password_override = normalized_passphrase.as_deref();
+    let signature_result = if encryption == Encryption::Ethereum && is_bip44_path(path) {
+        let mut normalized_phrase: String = seed_phrase.nfkd().collect();
+        let pair_result = bip44_pair(&normalized_phrase, path, password_override);
+        normalized_phrase.zeroize();
+        pair_result.map(|pair| MultiSignature::Ecdsa(pair.sign_prehashed(digest)))
+    } else {
+        let mut full_address: String = seed_phrase.nfkd().chain(path.nfkd()).collect();
+        let signature_result = ecdsa::Pair::from_string(&full_address, password_override)
+            .map(|pair| MultiSignature::Ecdsa(pair.sign_prehashed(digest)));
+        full_address.zeroize();
+        signature_result.map_err(IdentityError::SecretStringError)
+    };
+    if let Some(passphrase) = normalized_passphrase.as_mut() {
+        passphrase.zeroize();
+    }
+    signature_result
+}
+
+/// Longest transaction payload signed as is, longer ones are signed by hash
+const MAX_UNHASHED_PAYLOAD_LEN: usize = 256;
+
+/// Data to be signed, with its kind explicit in the type
+///
+/// Each kind is turned into signed bytes its own way, so that a transaction
+/// could never be signed as a plain message, and a plain message could
+/// never pass as a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ToSign {
+    /// Transaction signing payload: call with signed extensions
+    Extrinsic(Vec<u8>),
+
+    /// Plain message, e.g. for dApp login or proof of ownership
+    PlainMessage(String),
+
+    /// 32-byte digest made elsewhere, see [`sign_prehashed`]
+    Prehashed([u8; 32]),
+}
+
+impl ToSign {
+    /// Bytes that are actually signed
+    ///
+    /// - `Extrinsic` payload is signed as is, or as its `blake2_256` hash if
+    ///   it is longer than 256 bytes, as Substrate runtimes expect
+    /// - `PlainMessage` is wrapped as `<Bytes>{message}</Bytes>`, the wrapping
+    ///   makes it impossible to interpret as a transaction
+    /// - `Prehashed` digest is signed without any changes
+    ///
+    /// Except for `Prehashed`, the bytes are then signed as a message, see
+    /// [`sign_message`], and are hashed again by `Ecdsa` and `Ethereum`, with
+    /// `blake2_256` and `keccak_256` respectively.
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        match self {
+            ToSign::Extrinsic(payload) => {
+                if payload.len() > MAX_UNHASHED_PAYLOAD_LEN {
+                    blake2_256(payload).to_vec()
+                } else {
+                    payload.to_vec()
+                }
+            }
+            ToSign::PlainMessage(message) => format!("<Bytes>{message}</Bytes>").into_bytes(),
+            ToSign::Prehashed(digest) => digest.to_vec(),
+        }
+    }
+}
+
+/// Sign [`ToSign`] data with the key derived from seed phrase and
+/// derivation path
+///
+/// Single entry point for signing: the data kind decides the signed bytes,
+/// see [`ToSign::signed_bytes`]. `Ethereum` signs `keccak_256` hash of
+/// these bytes, both for extrinsics and for messages, as Ethereum-compatible
+/// chains expect. `Prehashed` is supported only for `Ecdsa` and `Ethereum`,
+/// same as in [`sign_prehashed`].
+pub fn sign(
+    to_sign: ToSign,
+    seed_phrase: &str,
+    derivation_path: &str,
+    passphrase: Option<&str>,
+    encryption: Encryption,
+) -> IdentityResult<MultiSignature> {
+    match to_sign {
+        ToSign::Prehashed(digest) => sign_prehashed(
+            seed_phrase,
+            derivation_path,
+            passphrase,
+            encryption,
+            &digest,
+        ),
+        _ => sign_message(
+            seed_phrase,
+            derivation_path,
+            passphrase,
+            encryption,
+            &to_sign.signed_bytes(),
+        ),
+    }
+}
+
+/// Sign `message` in Ethereum `personal_sign` format
+///
+/// Signed digest is [`eth_message_hash`] of the message. Signature is 65-byte
+/// `r || s || v`, with `v` being `27` or `28`, as dApps expect it, and could
+/// be checked with [`eth_recover`](helpers::eth_recover).
+///
+/// Key is derived the same way as for `Ethereum` addresses created in Vault,
+/// with the same BIP39 `passphrase`, if any.
+pub fn eth_personal_sign(
+    seed_phrase: &str,
+    path: &str,
+    passphrase: Option<&str>,
+    message: &[u8],
+) -> IdentityResult<[u8; 65]> {
+    let digest = eth_message_hash(message);
+    match sign_prehashed(seed_phrase, path, passphrase, Encryption::Ethereum, &digest)? {
+        MultiSignature::Ecdsa(signature) => {
+            let mut signature = signature.0;
+            signature[64] += 27;
+            Ok(signature)
+        }
+        _ => unreachable!("ecdsa key always produces ecdsa signature"),
+    }
+}
+
+/// Make [`AddressOutcome`] for already derived address
+///
+/// New address inherits `parent_secret_exposed`: if any address of the seed
+/// had its secret exposed, the seed itself could be compromised, and so are
+/// all the addresses derived from it later. Flag is never cleared this way,
+/// only set.
 fn do_create_address_with_seed_phrase(
     cropped_path: &str,
     network_specs: Option<&NetworkSpecs>,
+    encryption: Encryption,
     seed_name: &str,
     multisigner: MultiSigner,
     has_pwd: bool,
-) -> IdentityResult<(Option<AddressDetails>, Option<IdentityRecord>)> {
+    parent_secret_exposed: bool,
+) -> IdentityResult<AddressOutcome> {
     // Check that the seed name is not empty.
     if seed_name.is_empty() {
         return Err(IdentityError::EmptySeedName);
     }
+    let public_key = multisigner_to_public(&multisigner);
     let mut identity_record: Option<IdentityRecord> = None;
-    let mut address_details: Option<AddressDetails> = None;
     let mut address_key: Option<AddressKey> = None;
     let mut network_specs_key: Option<NetworkSpecsKey> = None;
     if let Some(network_specs) = network_specs {
         network_specs_key = Some(NetworkSpecsKey::from_parts(
             &network_specs.genesis_hash,
-            &network_specs.encryption,
-        ));
+            &encryption,
+        )?);
 
-        let public_key = multisigner_to_public(&multisigner);
-        address_key = Some(AddressKey::new(
-            multisigner.clone(),
-            Some(network_specs.genesis_hash),
-        ));
+        address_key = Some(AddressKey::for_network(multisigner.clone(), network_specs));
         identity_record = Some(IdentityRecord::get(
             seed_name,
-            &network_specs.encryption,
+            &encryption,
             &public_key,
             cropped_path,
             network_specs.genesis_hash,
@@ -109,96 +1000,1891 @@ fn do_create_address_with_seed_phrase(
         address_key = Some(AddressKey::new(multisigner.clone(), None))
     }
 
-    address_details = Some(AddressDetails {
+    let details = AddressDetails {
         seed_name: seed_name.to_string(),
         path: cropped_path.to_string(),
         has_pwd,
         network_id: network_specs_key,
-        encryption: network_specs
-            .map(|ns| ns.encryption)
-            .unwrap_or(Encryption::Sr25519),
-        secret_exposed: false,
-    });
+        encryption,
+        public_key,
+        secret_exposed: parent_secret_exposed,
+    };
 
-    Ok((address_details, identity_record))
+    Ok(match identity_record {
+        Some(record) => AddressOutcome::WithNetwork { details, record },
+        None => AddressOutcome::NetworkAgnostic { details },
+    })
 }
 
 fn create_address_with_seed_phrase(
     network_specs: Option<&NetworkSpecs>,
-    derivation_path: &'static str,
-    seed_phrase: String,
-    seed_name: &'static str,
-) -> IdentityResult<(Option<AddressDetails>, Option<IdentityRecord>)> {
+    encryption: Encryption,
+    derivation_path: &str,
+    seed_phrase: &str,
+    seed_name: &str,
+    passphrase: Option<String>,
+    parent_secret_exposed: bool,
+) -> IdentityResult<AddressOutcome> {
     // Check that the seed name is not empty.
     if seed_phrase.is_empty() {
         return Err(IdentityError::EmptySeed);
     }
-    // create fixed-length string to avoid reallocations
-    let full_address_size = seed_phrase.len() + derivation_path.len();
-    let mut full_address = String::with_capacity(full_address_size);
-    full_address.push_str(seed_phrase.as_str());
-    full_address.push_str(derivation_path);
-
-    let encryption = network_specs
-        .map(|ns| ns.encryption)
-        .unwrap_or(Encryption::Sr25519);
+    if has_empty_junction(derivation_path) {
+        return Err(IdentityError::EmptyJunction);
+    }
+    // checked before the derivation, which is slow for long passwords
+    if let Some(caps) = REG_PATH.captures(derivation_path) {
+        check_password_len(&caps)?;
+    }
+    // BIP39 requires NFKD normalization of both the phrase and the password,
+    // otherwise same phrase with composed and decomposed characters would
+    // produce different keys
+    let mut normalized_phrase: String = seed_phrase.nfkd().collect();
+    let mut normalized_path: String = derivation_path.nfkd().collect();
+    let normalized_passphrase: Option<String> = passphrase.map(|mut passphrase| {
+        let normalized = passphrase.nfkd().collect();
+        passphrase.zeroize();
+        normalized
+    });
+    let has_passphrase = normalized_passphrase.is_some();
 
-    let multisigner = full_address_to_multisigner(full_address, encryption)?;
+    let res = full_address_to_multisigner(
+        &normalized_phrase,
+        &normalized_path,
+        normalized_passphrase,
+        encryption,
+    )
+    .and_then(|multisigner| {
+        // BIP32 path has no password part and is stored as is
+        let (cropped_path, has_pwd) =
+            if encryption == Encryption::Ethereum && is_bip44_path(&normalized_path) {
+                (normalized_path.as_str(), false)
+            } else {
+                match REG_PATH.captures(&normalized_path) {
+                    Some(caps) => match caps.name("path") {
+                        Some(a) => (a.as_str(), caps.name("password").is_some()),
+                        None => ("", caps.name("password").is_some()),
+                    },
+                    None => ("", false),
+                }
+            };
+        let has_pwd = has_pwd || has_passphrase;
 
-    let (cropped_path, has_pwd) = match REG_PATH.captures(derivation_path) {
-        Some(caps) => match caps.name("path") {
-            Some(a) => (a.as_str(), caps.name("password").is_some()),
-            None => ("", caps.name("password").is_some()),
-        },
-        None => ("", false),
-    };
+        do_create_address_with_seed_phrase(
+            cropped_path,
+            network_specs,
+            encryption,
+            seed_name,
+            multisigner,
+            has_pwd,
+            parent_secret_exposed,
+        )
+    });
+    normalized_phrase.zeroize();
+    normalized_path.zeroize();
+    res
+}
 
-    let res = do_create_address_with_seed_phrase(
-        cropped_path,
-        network_specs,
+/// Create address from substrate secret URI, e.g.
+/// `bottom drive obey lake curtain smoke basket hold race lonely fit walk//Alice///password`
+///
+/// URI is the seed phrase, followed by the derivation path with optional
+/// password, in the format `REG_PATH` handles. Bare seed phrase creates an
+/// address with empty derivation path. URI is only borrowed, all derived
+/// copies of the secret are zeroized.
+pub fn create_address_from_uri(
+    specs: Option<&NetworkSpecs>,
+    uri: &str,
+    seed_name: &str,
+) -> IdentityResult<AddressOutcome> {
+    let (seed_phrase, derivation_path) = uri.split_at(uri.find('/').unwrap_or(uri.len()));
+    let encryption = specs.map(|ns| ns.encryption).unwrap_or_default();
+    create_address_with_seed_phrase(
+        specs,
+        encryption,
+        derivation_path,
+        seed_phrase,
         seed_name,
-        multisigner,
-        has_pwd,
-    )?;
-    Ok(res)
+        None,
+        false,
+    )
 }
 
-fn create_address(
+/// Create address for the seed that already has `existing` addresses
+///
+/// Same as address creation through [`CreateAddressPayload::SeedPhrase`],
+/// but the new address is marked `secret_exposed` if any of the seed
+/// addresses is, see [`seed_secret_exposed`]. Addresses of other seeds in
+/// `existing` are ignored.
+pub fn create_address_for_seed(
     network_specs: Option<&NetworkSpecs>,
-    payload: CreateAddressPayload,
-) -> IdentityResult<(Option<AddressDetails>, Option<IdentityRecord>)> {
-    match payload {
-        CreateAddressPayload::SeedPhrase {
-            seed_name,
-            seed_phrase,
-            derivation_path,
-        } => {
-            create_address_with_seed_phrase(network_specs, derivation_path, seed_phrase, seed_name)
-        }
-        _ => unimplemented!(),
-    }
+    derivation_path: &str,
+    seed_phrase: &str,
+    seed_name: &str,
+    existing: &[AddressDetails],
+) -> IdentityResult<AddressOutcome> {
+    let encryption = network_specs.map(|ns| ns.encryption).unwrap_or_default();
+    create_address_with_seed_phrase(
+        network_specs,
+        encryption,
+        derivation_path,
+        seed_phrase,
+        seed_name,
+        None,
+        seed_secret_exposed(seed_name, existing),
+    )
+}
+
+/// Minimal length of the password for email and password accounts
+const MIN_ACCOUNT_PASSWORD_LEN: usize = 8;
+
+/// Argon2id memory cost, in KiB, for email and password accounts
+const ACCOUNT_KDF_M_COST: u32 = 19 * 1024;
+
+/// Argon2id number of passes for email and password accounts
+const ACCOUNT_KDF_T_COST: u32 = 2;
+
+/// Derive seed phrase for email and password account
+///
+/// Derivation is **deterministic**: same email and password always regenerate
+/// the same seed phrase, and therefore the same keys, on any device. Nothing
+/// is stored, and losing the password means losing the keys.
+///
+/// 32 bytes of entropy for a 24-word phrase are produced by Argon2id with
+/// the password as input and `sha256` of the email as salt. Email is trimmed
+/// and lowercased, so that `Alice@Example.com` and `alice@example.com` are
+/// the same account. KDF parameters are part of the key derivation, and
+/// must never change.
+///
+/// The output is a **secret seed phrase**, returned as [`SecretPhrase`] to
+/// be zeroized on drop.
+fn seed_phrase_from_email_and_password(
+    email: &str,
+    password: &str,
+) -> IdentityResult<SecretPhrase> {
+    let email = email.trim().to_lowercase();
+    if email.is_empty() {
+        return Err(IdentityError::EmptyEmail);
+    }
+    if password.chars().count() < MIN_ACCOUNT_PASSWORD_LEN {
+        return Err(IdentityError::PasswordTooShort(MIN_ACCOUNT_PASSWORD_LEN));
+    }
+    let params = argon2::Params::new(ACCOUNT_KDF_M_COST, ACCOUNT_KDF_T_COST, 1, Some(32))
+        .expect("known value");
+    let kdf = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let mut password: String = password.nfkd().collect();
+    let mut entropy = [0u8; 32];
+    kdf.hash_password_into(
+        password.as_bytes(),
+        &sha2_256(email.as_bytes()),
+        &mut entropy,
+    )
+    .expect("salt and output lengths are within argon2 limits");
+    password.zeroize();
+    let seed_phrase = SecretPhrase(
+        Mnemonic::from_entropy(&entropy, Language::English)
+            .expect("32 bytes is valid BIP39 entropy length")
+            .into_phrase(),
+    );
+    entropy.zeroize();
+    Ok(seed_phrase)
+}
+
+/// Create addresses for several derivation paths of the same seed phrase,
+/// e.g. to scan first accounts `//0` to `//19`
+///
+/// Seed phrase is converted into the root key once, and each path only
+/// applies its junctions to it. Paths with password are the exception: in
+/// substrate the password goes into the seed itself, so such paths are
+/// derived from the phrase anew, and so are BIP32 paths of `Ethereum`
+/// networks, e.g. `m/44'/60'/0'/0/0`, see [`is_bip44_path`]. Normalized seed
+/// phrase is zeroized once, when all paths are done.
+///
+/// New addresses are marked `secret_exposed` if any of the seed `existing`
+/// addresses is, see [`seed_secret_exposed`].
+///
+/// Output is in `paths` order; error in any path fails the whole batch.
+pub fn create_addresses_batch(
+    network_specs: Option<&NetworkSpecs>,
+    seed_phrase: &str,
+    seed_name: &str,
+    paths: &[&str],
+    existing: &[AddressDetails],
+) -> IdentityResult<Vec<AddressOutcome>> {
+    let encryption = network_specs.map(|ns| ns.encryption).unwrap_or_default();
+    let targets: Vec<(Option<&NetworkSpecs>, &str)> =
+        paths.iter().map(|path| (network_specs, *path)).collect();
+    let parent_secret_exposed = seed_secret_exposed(seed_name, existing);
+    derive_batch(
+        encryption,
+        seed_phrase,
+        seed_name,
+        &targets,
+        parent_secret_exposed,
+    )
+}
+
+/// Derive addresses for all `targets`, pairs of network specs and
+/// derivation path, from single root key of given [`Encryption`]
+///
+/// All addresses inherit `parent_secret_exposed`. Output is in `targets`
+/// order.
+fn derive_batch(
+    encryption: Encryption,
+    seed_phrase: &str,
+    seed_name: &str,
+    targets: &[(Option<&NetworkSpecs>, &str)],
+    parent_secret_exposed: bool,
+) -> IdentityResult<Vec<AddressOutcome>> {
+    if seed_phrase.is_empty() {
+        return Err(IdentityError::EmptySeed);
+    }
+    let mut normalized_phrase: String = seed_phrase.nfkd().collect();
+    let res = match encryption {
+        Encryption::Ed25519 => derive_batch_with::<ed25519::Pair>(
+            encryption,
+            &normalized_phrase,
+            seed_name,
+            targets,
+            parent_secret_exposed,
+        ),
+        Encryption::Sr25519 => derive_batch_with::<sr25519::Pair>(
+            encryption,
+            &normalized_phrase,
+            seed_name,
+            targets,
+            parent_secret_exposed,
+        ),
+        Encryption::Ecdsa | Encryption::Ethereum => derive_batch_with::<ecdsa::Pair>(
+            encryption,
+            &normalized_phrase,
+            seed_name,
+            targets,
+            parent_secret_exposed,
+        ),
+    };
+    normalized_phrase.zeroize();
+    res
+}
+
+/// Derive addresses for all `targets` from single root key of scheme `P`
+fn derive_batch_with<P>(
+    encryption: Encryption,
+    normalized_phrase: &str,
+    seed_name: &str,
+    targets: &[(Option<&NetworkSpecs>, &str)],
+    parent_secret_exposed: bool,
+) -> IdentityResult<Vec<AddressOutcome>>
+where
+    P: Pair,
+    P::Public: Into<MultiSigner>,
+{
+    let (root, mut seed) =
+        P::from_phrase(normalized_phrase, None).map_err(IdentityError::SecretStringError)?;
+    // only the root key is needed
+    seed.as_mut().zeroize();
+
+    let mut outcomes = Vec::with_capacity(targets.len());
+    for (network_specs, path) in targets {
+        let mut normalized_path: String = path.nfkd().collect();
+        let outcome = derive_from_root(
+            &root,
+            *network_specs,
+            encryption,
+            normalized_phrase,
+            seed_name,
+            &normalized_path,
+            parent_secret_exposed,
+        );
+        normalized_path.zeroize();
+        outcomes.push(outcome?);
+    }
+    Ok(outcomes)
+}
+
+/// Derive address for single path, from the root key if possible
+fn derive_from_root<P>(
+    root: &P,
+    network_specs: Option<&NetworkSpecs>,
+    encryption: Encryption,
+    normalized_phrase: &str,
+    seed_name: &str,
+    normalized_path: &str,
+    parent_secret_exposed: bool,
+) -> IdentityResult<AddressOutcome>
+where
+    P: Pair,
+    P::Public: Into<MultiSigner>,
+{
+    if has_empty_junction(normalized_path) {
+        return Err(IdentityError::EmptyJunction);
+    }
+    // BIP32 path is derived from the phrase words, not from the root key,
+    // same as in single address creation
+    if encryption == Encryption::Ethereum && is_bip44_path(normalized_path) {
+        return create_address_with_seed_phrase(
+            network_specs,
+            encryption,
+            normalized_path,
+            normalized_phrase,
+            seed_name,
+            None,
+            parent_secret_exposed,
+        );
+    }
+    let info = parse_derivation_path(normalized_path)?;
+    if info.has_pwd {
+        return create_address_with_seed_phrase(
+            network_specs,
+            encryption,
+            normalized_path,
+            normalized_phrase,
+            seed_name,
+            None,
+            parent_secret_exposed,
+        );
+    }
+    // same junction parsing as in substrate `from_string`: numeric names
+    // become integer junctions
+    let junctions = info.junctions.iter().map(|junction| match junction {
+        Junction::Soft(name) => DeriveJunction::from(name),
+        Junction::Hard(name) => DeriveJunction::from(format!("/{name}")),
+    });
+    let (pair, _) = root
+        .derive(junctions, None)
+        .map_err(|_| IdentityError::SecretStringError(SecretStringError::InvalidPath))?;
+    do_create_address_with_seed_phrase(
+        &info.cropped_path,
+        network_specs,
+        encryption,
+        seed_name,
+        pair.public().into(),
+        false,
+        parent_secret_exposed,
+    )
+}
+
+/// How addresses are derived for multiple networks from a single seed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DerivationStrategy {
+    /// Seed key itself, with empty derivation path, is used in all networks,
+    /// and is only displayed with network-specific base58 prefix.
+    ///
+    /// Convenient, since user has a single account everywhere, but any
+    /// activity in one network is linkable to activity in all others.
+    Shared,
+
+    /// Each network gets its own key, derived with network `path_id`.
+    ///
+    /// Accounts in different networks could not be linked with each other by
+    /// public keys, and compromised network-specific key does not affect
+    /// other networks.
+    PerNetwork,
+}
+
+/// Create addresses for seed phrase in every network from `specs`, following
+/// the [`DerivationStrategy`]
+///
+/// With [`DerivationStrategy::Shared`] the public key is same in all networks
+/// with same [`Encryption`]; networks of different encryption still get
+/// different keys.
+///
+/// Seed phrase is converted into the root key once per [`Encryption`] found
+/// in `specs`, same as in [`create_addresses_batch`], and new addresses
+/// inherit `secret_exposed` from the seed `existing` addresses the same way.
+/// Output is in `specs` order.
+pub fn create_accounts(
+    strategy: DerivationStrategy,
+    seed_phrase: &str,
+    seed_name: &str,
+    specs: &[NetworkSpecs],
+    existing: &[AddressDetails],
+) -> IdentityResult<Vec<AddressDetails>> {
+    let parent_secret_exposed = seed_secret_exposed(seed_name, existing);
+    let encryptions: BTreeSet<Encryption> = specs.iter().map(|ns| ns.encryption).collect();
+    let mut accounts: Vec<Option<AddressDetails>> = vec![None; specs.len()];
+    for encryption in encryptions {
+        let (positions, targets): (Vec<usize>, Vec<(Option<&NetworkSpecs>, &str)>) = specs
+            .iter()
+            .enumerate()
+            .filter(|(_, network_specs)| network_specs.encryption == encryption)
+            .map(|(position, network_specs)| {
+                let derivation_path = match strategy {
+                    DerivationStrategy::Shared => "",
+                    DerivationStrategy::PerNetwork => network_specs.path_id.as_str(),
+                };
+                (position, (Some(network_specs), derivation_path))
+            })
+            .unzip();
+        let outcomes = derive_batch(
+            encryption,
+            seed_phrase,
+            seed_name,
+            &targets,
+            parent_secret_exposed,
+        )?;
+        for (position, outcome) in positions.into_iter().zip(outcomes) {
+            accounts[position] = Some(outcome.into_parts().0);
+        }
+    }
+    Ok(accounts.into_iter().flatten().collect())
+}
+
+/// Prefix of unsigned `derivations` import payload: `53`, unsigned crypto
+/// `ff`, payload type `de`
+const DERIVATIONS_PREFIX: [u8; 3] = [0x53, 0xff, 0xde];
+
+/// Parse Vault `derivations` bulk import payload
+///
+/// Payload content is SCALE-encoded network [`Encryption`] and genesis hash,
+/// followed by the derivation paths to be created for the seed. Network of
+/// the derivations is available through [`derivations_import_network`].
+///
+/// All derivations are checked with `REG_PATH`; the whole batch is rejected
+/// if any of derivations is invalid or has password, passworded derivations
+/// are never imported in bulk.
+pub fn parse_derivations_import(bytes: &[u8]) -> DefinitionResult<Vec<String>> {
+    let (_, _, derivations) = decode_derivations_import(bytes)?;
+    if let Some(invalid) = derivations.iter().find(|derivation| {
+        REG_PATH
+            .captures(derivation)
+            .is_none_or(|caps| caps.name("password").is_some())
+    }) {
+        let shown = match REG_PATH.captures(invalid) {
+            Some(caps) => format!("{}///<password>", &caps["path"]),
+            None => invalid.to_string(),
+        };
+        return Err(DefinitionError::InvalidDerivation(shown));
+    }
+    Ok(derivations)
+}
+
+/// Get [`NetworkSpecsKey`] of the network the Vault `derivations` bulk
+/// import payload is for
+pub fn derivations_import_network(bytes: &[u8]) -> DefinitionResult<NetworkSpecsKey> {
+    let (encryption, genesis_hash, _) = decode_derivations_import(bytes)?;
+    NetworkSpecsKey::from_parts(&genesis_hash, &encryption)
+}
+
+/// Decode `derivations` import payload content
+fn decode_derivations_import(bytes: &[u8]) -> DefinitionResult<(Encryption, H256, Vec<String>)> {
+    let mut content = bytes
+        .strip_prefix(&DERIVATIONS_PREFIX[..])
+        .ok_or(DefinitionError::NotDerivationsImport)?;
+    Ok(<(Encryption, H256, Vec<String>)>::decode(&mut content)?)
+}
+
+/// Create address of a seed that has no addresses yet
+///
+/// Addresses of a seed with `existing` addresses should be created with
+/// [`create_address_with_encryption`], to inherit `secret_exposed`.
+fn create_address(
+    network_specs: Option<&NetworkSpecs>,
+    payload: CreateAddressPayload,
+) -> IdentityResult<AddressOutcome> {
+    let encryption = network_specs.map(|ns| ns.encryption).unwrap_or_default();
+    create_address_with_encryption(network_specs, payload, encryption, &[])
+}
+
+/// Create address with given [`Encryption`], instead of the network default
+/// one.
+///
+/// Address is still bound to the network genesis hash, but its
+/// [`NetworkSpecsKey`] is built with the chosen encryption. This is valid for
+/// networks that support several encryption algorithms, e.g. substrate
+/// networks accepting both `Sr25519` and `Ed25519` signatures; such networks
+/// have separate network specs entry for each encryption.
+///
+/// New address is marked `secret_exposed` if any of the seed `existing`
+/// addresses is, see [`seed_secret_exposed`].
+fn create_address_with_encryption(
+    network_specs: Option<&NetworkSpecs>,
+    payload: CreateAddressPayload,
+    encryption: Encryption,
+    existing: &[AddressDetails],
+) -> IdentityResult<AddressOutcome> {
+    match payload {
+        CreateAddressPayload::SeedPhrase {
+            seed_name,
+            seed_phrase,
+            derivation_path,
+            passphrase,
+        } => create_address_with_seed_phrase(
+            network_specs,
+            encryption,
+            derivation_path,
+            seed_phrase,
+            seed_name,
+            passphrase,
+            seed_secret_exposed(seed_name, existing),
+        ),
+        CreateAddressPayload::EmailAndPassword {
+            email,
+            password,
+            derivation_path,
+            seed_name,
+        } => {
+            // zeroized on drop
+            let seed_phrase = seed_phrase_from_email_and_password(email, password)?;
+            create_address_with_seed_phrase(
+                network_specs,
+                encryption,
+                derivation_path,
+                &seed_phrase,
+                seed_name,
+                None,
+                seed_secret_exposed(seed_name, existing),
+            )
+        }
+        CreateAddressPayload::SocialProvider => unimplemented!(),
+    }
 }
 
 fn main() {
-    let seed_phrase = generate_random_phrase(24).unwrap();
-    println!("{:?}", seed_phrase.clone());
+    let seed_phrase = SecretString::from(generate_random_phrase(24).unwrap());
+
+    with_seed(seed_phrase, |seed_phrase| {
+        let sr25519_pair = sr25519::Pair::from_string(seed_phrase, None)
+            .map_err(IdentityError::SecretStringError)
+            .unwrap();
+        println!("{:?}", sr25519_pair.public());
+        for network_spec in default_network_specs() {
+            let (address_details, identity_record) = create_address(
+                Some(&network_spec),
+                CreateAddressPayload::SeedPhrase {
+                    derivation_path: "//Alice",
+                    seed_phrase,
+                    seed_name: "Alice",
+                    passphrase: None,
+                },
+            )
+            .unwrap()
+            .into_parts();
+
+            println!("{:?}", address_details);
+            println!("{:?}", identity_record);
+        }
+    });
+
+    multilingual_demo();
+}
+
+/// Substrate development seed phrase, source of the fixed non-English
+/// phrases in [`multilingual_demo`]
+const DEMO_PHRASE: &str = "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+/// `//Alice` `Sr25519` address of [`DEMO_PHRASE`], with prefix `42`
+const DEMO_ALICE_SS58: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+/// Generate and check French and Japanese phrases, and derive addresses
+/// from them
+///
+/// Keys are derived from the phrase entropy, so a fixed phrase in any
+/// language must give the same address as its English counterpart.
+/// Japanese phrases are written with ideographic spaces, and go through
+/// [`normalize_phrase`] before validation.
+fn multilingual_demo() {
+    let mut entropy = entropy_from_phrase(DEMO_PHRASE).unwrap();
+    for language in [Language::French, Language::Japanese] {
+        let phrase = generate_random_phrase_in(12, language).unwrap();
+        validate_seed_phrase_in(&phrase, language).unwrap();
+        println!("{:?}: {}", language, demo_alice_address(&phrase, language));
+
+        let mut fixed = Mnemonic::from_entropy(&entropy, language)
+            .unwrap()
+            .into_phrase();
+        if language == Language::Japanese {
+            let mut ideographic = fixed.replace(' ', "\u{3000}");
+            std::mem::swap(&mut fixed, &mut ideographic);
+            ideographic.zeroize();
+        }
+        let mut normalized = normalize_phrase(&fixed);
+        validate_seed_phrase_in(&normalized, language).unwrap();
+        assert_eq!(demo_alice_address(&fixed, language), DEMO_ALICE_SS58);
+        println!("{:?}, fixed phrase: {}", language, DEMO_ALICE_SS58);
+        normalized.zeroize();
+        fixed.zeroize();
+    }
+    entropy.zeroize();
+}
+
+/// `//Alice` `Sr25519` address, with prefix `42`, of the phrase in `language`
+fn demo_alice_address(phrase: &str, language: Language) -> String {
+    let english = to_english_phrase(phrase, language).unwrap();
+    let (address_details, _) = create_address(
+        None,
+        CreateAddressPayload::SeedPhrase {
+            derivation_path: "//Alice",
+            seed_phrase: &english,
+            seed_name: "Demo",
+            passphrase: None,
+        },
+    )
+    .unwrap()
+    .into_parts();
+    address_details
+        .address_string(&address_details.public_key, 42)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use codec::Encode;
+
+    /// Substrate development seed phrase
+    const DEV_PHRASE: &str =
+        "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+    /// Hardhat and Anvil development seed phrase
+    const HARDHAT_PHRASE: &str = "test test test test test test test test test test test junk";
+
+    /// First MetaMask account of [`HARDHAT_PHRASE`]
+    const HARDHAT_ACCOUNT: &str = "f39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+    /// `//Alice` `Sr25519` address of [`DEV_PHRASE`], with prefix `42`
+    const ALICE_SS58: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    /// `Sr25519` root public key of [`DEV_PHRASE`]
+    const DEV_ROOT_SR25519: &str =
+        "46ebddef8cd9bb167dc30878d7113b7e168e6f0646beffd77d69d39bad76b47a";
+
+    fn derivations_payload(derivations: &[&str]) -> Vec<u8> {
+        let derivations: Vec<String> = derivations.iter().map(|d| d.to_string()).collect();
+        let content = (
+            Encryption::Sr25519,
+            NetworkSpecs::westend().genesis_hash,
+            derivations,
+        );
+        [&DERIVATIONS_PREFIX[..], &content.encode()].concat()
+    }
+
+    #[test]
+    fn empty_junction_rejected() {
+        for path in ["/", "//", "//a//", "//a/", "//a//b//"] {
+            assert!(has_empty_junction(path), "{path}");
+        }
+        for path in ["", "//a", "//a/b", "//a///password", "///password"] {
+            assert!(!has_empty_junction(path), "{path}");
+        }
+        let result = create_address_with_seed_phrase(
+            None,
+            Encryption::Sr25519,
+            "//",
+            DEV_PHRASE,
+            "Alice",
+            None,
+            false,
+        );
+        assert!(matches!(result, Err(IdentityError::EmptyJunction)));
+        assert!(matches!(
+            sign_message(
+                DEV_PHRASE,
+                "//Alice//",
+                None,
+                Encryption::Sr25519,
+                b"message"
+            ),
+            Err(IdentityError::EmptyJunction)
+        ));
+    }
+
+    #[test]
+    fn create_accounts_strategies() {
+        let ed25519_network = NetworkSpecs {
+            encryption: Encryption::Ed25519,
+            genesis_hash: H256::repeat_byte(7),
+            path_id: String::from("//custom"),
+            ..NetworkSpecs::westend()
+        };
+        let specs = [
+            NetworkSpecs::polkadot(),
+            ed25519_network,
+            NetworkSpecs::kusama(),
+        ];
+        for strategy in [DerivationStrategy::Shared, DerivationStrategy::PerNetwork] {
+            let accounts = create_accounts(strategy, DEV_PHRASE, "Alice", &specs, &[]).unwrap();
+            assert_eq!(accounts.len(), specs.len());
+            for (details, network_specs) in accounts.iter().zip(&specs) {
+                let path = match strategy {
+                    DerivationStrategy::Shared => "",
+                    DerivationStrategy::PerNetwork => network_specs.path_id.as_str(),
+                };
+                let (single, _) = create_address_with_seed_phrase(
+                    Some(network_specs),
+                    network_specs.encryption,
+                    path,
+                    DEV_PHRASE,
+                    "Alice",
+                    None,
+                    false,
+                )
+                .unwrap()
+                .into_parts();
+                assert_eq!(details, &single);
+            }
+            let shared_key = accounts[0].public_key == accounts[2].public_key;
+            assert_eq!(shared_key, strategy == DerivationStrategy::Shared);
+        }
+        let shared =
+            create_accounts(DerivationStrategy::Shared, DEV_PHRASE, "Alice", &specs, &[]).unwrap();
+        assert_eq!(hex::encode(&shared[0].public_key), DEV_ROOT_SR25519);
+        assert_eq!(shared[1].encryption, Encryption::Ed25519);
+    }
+
+    /// Ethereum address recovered from `Ecdsa` signature of `digest`
+    fn ecdsa_signer(signature: &MultiSignature, digest: &[u8; 32]) -> String {
+        let MultiSignature::Ecdsa(signature) = signature else {
+            panic!("expected ecdsa signature, got {signature:?}");
+        };
+        let public = signature.recover_prehashed(digest).unwrap();
+        hex::encode(helpers::ecdsa_public_to_eth_address(&public).unwrap())
+    }
+
+    #[test]
+    fn ethereum_signs_keccak_hash() {
+        let message = b"hello moonbeam";
+        let signature = sign_message(
+            HARDHAT_PHRASE,
+            "m/44'/60'/0'/0/0",
+            None,
+            Encryption::Ethereum,
+            message,
+        )
+        .unwrap();
+        assert_eq!(
+            ecdsa_signer(&signature, &keccak_256(message)),
+            HARDHAT_ACCOUNT
+        );
+
+        let public = ecdsa::Pair::from_string(&format!("{DEV_PHRASE}//Alice"), None)
+            .unwrap()
+            .public();
+        let signature =
+            sign_message(DEV_PHRASE, "//Alice", None, Encryption::Ethereum, message).unwrap();
+        let MultiSignature::Ecdsa(ethereum) = &signature else {
+            panic!("expected ecdsa signature");
+        };
+        assert!(ecdsa::Pair::verify_prehashed(
+            ethereum,
+            &keccak_256(message),
+            &public
+        ));
+        assert!(!ecdsa::Pair::verify(ethereum, message, &public));
+
+        let signature =
+            sign_message(DEV_PHRASE, "//Alice", None, Encryption::Ecdsa, message).unwrap();
+        let MultiSignature::Ecdsa(substrate) = &signature else {
+            panic!("expected ecdsa signature");
+        };
+        assert!(ecdsa::Pair::verify(substrate, message, &public));
+    }
+
+    #[test]
+    fn ethereum_signs_keccak_hash_of_signed_bytes() {
+        let path = "m/44'/60'/0'/0/0";
+        let short = vec![7; MAX_UNHASHED_PAYLOAD_LEN];
+        let long = vec![7; MAX_UNHASHED_PAYLOAD_LEN + 1];
+        for (to_sign, signed) in [
+            (ToSign::Extrinsic(short.clone()), short),
+            (ToSign::Extrinsic(long.clone()), blake2_256(&long).to_vec()),
+            (
+                ToSign::PlainMessage(String::from("login")),
+                b"<Bytes>login</Bytes>".to_vec(),
+            ),
+        ] {
+            assert_eq!(to_sign.signed_bytes(), signed);
+            let signature =
+                sign(to_sign, HARDHAT_PHRASE, path, None, Encryption::Ethereum).unwrap();
+            assert_eq!(
+                ecdsa_signer(&signature, &keccak_256(&signed)),
+                HARDHAT_ACCOUNT
+            );
+        }
+
+        let digest = [7; 32];
+        let signature = sign(
+            ToSign::Prehashed(digest),
+            HARDHAT_PHRASE,
+            path,
+            None,
+            Encryption::Ethereum,
+        )
+        .unwrap();
+        assert_eq!(ecdsa_signer(&signature, &digest), HARDHAT_ACCOUNT);
+    }
+
+    #[test]
+    fn non_english_phrase_gives_same_address() {
+        let entropy = entropy_from_phrase(DEV_PHRASE).unwrap();
+        let french = Mnemonic::from_entropy(&entropy, Language::French)
+            .unwrap()
+            .into_phrase();
+        let japanese = Mnemonic::from_entropy(&entropy, Language::Japanese)
+            .unwrap()
+            .into_phrase()
+            .replace(' ', "\u{3000}");
+        for (phrase, language) in [(french, Language::French), (japanese, Language::Japanese)] {
+            assert_ne!(phrase, DEV_PHRASE);
+            let english = to_english_phrase(&phrase, language).unwrap();
+            assert_eq!(&*english, DEV_PHRASE);
+            let (address_details, _) = create_address(
+                None,
+                CreateAddressPayload::SeedPhrase {
+                    derivation_path: "//Alice",
+                    seed_phrase: &english,
+                    seed_name: "Alice",
+                    passphrase: None,
+                },
+            )
+            .unwrap()
+            .into_parts();
+            assert_eq!(
+                address_details
+                    .address_string(&address_details.public_key, 42)
+                    .unwrap(),
+                ALICE_SS58
+            );
+        }
+    }
+
+    /// Network specs with `Ethereum` encryption, e.g. Moonbeam-like chain
+    fn ethereum_specs() -> NetworkSpecs {
+        NetworkSpecs {
+            encryption: Encryption::Ethereum,
+            ..NetworkSpecs::westend()
+        }
+    }
+
+    #[test]
+    fn bip44_path_in_batch() {
+        let specs = ethereum_specs();
+        let path = "m/44'/60'/0'/0/0";
+        let single = create_address(
+            Some(&specs),
+            CreateAddressPayload::SeedPhrase {
+                derivation_path: path,
+                seed_phrase: HARDHAT_PHRASE,
+                seed_name: "Hardhat",
+                passphrase: None,
+            },
+        )
+        .unwrap();
+        let batch =
+            create_addresses_batch(Some(&specs), HARDHAT_PHRASE, "Hardhat", &[path, "//0"], &[])
+                .unwrap();
+        assert_eq!(batch[0], single);
+        assert_ne!(batch[1].details().public_key, single.details().public_key);
+
+        let single = single.details();
+        let address = single.address_string(&single.public_key, 0).unwrap();
+        assert_eq!(address.to_lowercase(), format!("0x{HARDHAT_ACCOUNT}"));
+
+        let accounts = create_accounts(
+            DerivationStrategy::PerNetwork,
+            HARDHAT_PHRASE,
+            "Hardhat",
+            &[NetworkSpecs {
+                path_id: path.to_string(),
+                ..specs
+            }],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(accounts, vec![single.clone()]);
+    }
+
+    /// Public key and displayed address created with BIP39 `passphrase`
+    fn address_with_passphrase(
+        network_specs: &NetworkSpecs,
+        seed_phrase: &str,
+        path: &str,
+        passphrase: &str,
+    ) -> (Vec<u8>, String) {
+        let (address_details, _) = create_address(
+            Some(network_specs),
+            CreateAddressPayload::SeedPhrase {
+                derivation_path: path,
+                seed_phrase,
+                seed_name: "Passphrase",
+                passphrase: Some(passphrase.to_string()),
+            },
+        )
+        .unwrap()
+        .into_parts();
+        let address = address_details
+            .address_string(&address_details.public_key, network_specs.base58prefix)
+            .unwrap();
+        (address_details.public_key, address.to_lowercase())
+    }
+
+    #[test]
+    fn signing_with_passphrase() {
+        let message = b"signed with passphrase";
+        let (public_key, _) =
+            address_with_passphrase(&NetworkSpecs::westend(), DEV_PHRASE, "//Alice", "secret");
+        let public = sr25519::Pair::from_string(&format!("{DEV_PHRASE}//Alice///secret"), None)
+            .unwrap()
+            .public();
+        assert_eq!(public_key, public.0.to_vec());
+        let signature = sign_message(
+            DEV_PHRASE,
+            "//Alice",
+            Some("secret"),
+            Encryption::Sr25519,
+            message,
+        )
+        .unwrap();
+        let MultiSignature::Sr25519(signature) = signature else {
+            panic!("expected sr25519 signature");
+        };
+        assert!(sr25519::Pair::verify(&signature, message, &public));
+
+        let path = "m/44'/60'/0'/0/0";
+        let (_, address) =
+            address_with_passphrase(&ethereum_specs(), HARDHAT_PHRASE, path, "secret");
+        assert_ne!(address, format!("0x{HARDHAT_ACCOUNT}"));
+        let signature = eth_personal_sign(HARDHAT_PHRASE, path, Some("secret"), message).unwrap();
+        let signer = helpers::eth_recover(message, &signature).unwrap();
+        assert_eq!(format!("0x{}", hex::encode(signer)), address);
 
-    let sr25519_pair = sr25519::Pair::from_string(&seed_phrase, None)
-        .map_err(IdentityError::SecretStringError)
+        let digest = [7; 32];
+        let signature = sign_prehashed(
+            HARDHAT_PHRASE,
+            path,
+            Some("secret"),
+            Encryption::Ethereum,
+            &digest,
+        )
         .unwrap();
-    println!("{:?}", sr25519_pair.public());
-    for network_spec in default_network_specs().to_vec() {
-        let (address_details, identity_record) = create_address(
-            Some(&network_spec),
+        assert_eq!(format!("0x{}", ecdsa_signer(&signature, &digest)), address);
+    }
+
+    #[test]
+    fn non_english_phrase_gives_other_bip39_seed() {
+        let entropy = entropy_from_phrase(DEV_PHRASE).unwrap();
+        let french = Mnemonic::from_entropy(&entropy, Language::French).unwrap();
+        let english = to_english_phrase(french.phrase(), Language::French).unwrap();
+        let english = Mnemonic::from_phrase(&english, Language::English).unwrap();
+        assert_eq!(english.entropy(), french.entropy());
+        assert_ne!(
+            Seed::new(&english, "").as_bytes(),
+            Seed::new(&french, "").as_bytes()
+        );
+    }
+
+    #[test]
+    fn phrases_are_secret() {
+        let entropy = entropy_from_phrase(DEV_PHRASE).unwrap();
+        let mut phrase = phrase_from_entropy(&entropy).unwrap();
+        assert_eq!(&*phrase, DEV_PHRASE);
+        phrase.zeroize();
+        assert!(phrase.is_empty());
+
+        let phrase = generate_phrase(PhraseStrength::Words24);
+        assert_eq!(phrase.split(' ').count(), 24);
+        validate_seed_phrase(&phrase).unwrap();
+
+        let (phrase, indices) = generate_phrase_with_indices(12).unwrap();
+        let words: Vec<&str> = phrase.split(' ').collect();
+        assert_eq!(words.len(), indices.len());
+        for (word, index) in words.iter().zip(indices) {
+            assert_eq!(
+                *word,
+                Language::English.wordlist().get_words_by_prefix("")[index as usize]
+            );
+        }
+    }
+
+    #[test]
+    fn complete_english_words() {
+        assert_eq!(complete_word("aban", Language::English).unwrap(), "abandon");
+        assert_eq!(complete_word("act", Language::English).unwrap(), "act");
+        assert!(matches!(
+            complete_word("ab", Language::English),
+            Err(IdentityError::AmbiguousWordPrefix(prefix)) if prefix == "ab"
+        ));
+        assert!(matches!(
+            complete_word("xyzq", Language::English),
+            Err(IdentityError::UnknownWordPrefix(_))
+        ));
+        for &word in Language::English.wordlist().get_words_by_prefix("") {
+            let prefix: String = word.chars().take(4).collect();
+            assert_eq!(complete_word(&prefix, Language::English).unwrap(), word);
+        }
+    }
+
+    #[test]
+    fn complete_words_in_unsorted_wordlists() {
+        for language in [Language::French, Language::Japanese, Language::Spanish] {
+            for &word in language.wordlist().get_words_by_prefix("") {
+                assert_eq!(complete_word(word, language).unwrap(), word);
+            }
+        }
+    }
+
+    #[test]
+    fn derivations_import() {
+        let payload = derivations_payload(&["//westend//0", "//westend/1", ""]);
+        assert_eq!(
+            parse_derivations_import(&payload).unwrap(),
+            ["//westend//0", "//westend/1", ""]
+        );
+        let westend = NetworkSpecs::westend();
+        assert_eq!(
+            derivations_import_network(&payload).unwrap(),
+            NetworkSpecsKey::from_parts(&westend.genesis_hash, &westend.encryption).unwrap()
+        );
+    }
+
+    #[test]
+    fn derivations_import_rejects_invalid_batch() {
+        let payload = derivations_payload(&["//0", "//1///secret"]);
+        assert!(matches!(
+            parse_derivations_import(&payload),
+            Err(DefinitionError::InvalidDerivation(shown)) if shown == "//1///<password>"
+        ));
+        let payload = derivations_payload(&["//0", "no slash"]);
+        assert!(matches!(
+            parse_derivations_import(&payload),
+            Err(DefinitionError::InvalidDerivation(shown)) if shown == "no slash"
+        ));
+        assert!(matches!(
+            parse_derivations_import(&payload[1..]),
+            Err(DefinitionError::NotDerivationsImport)
+        ));
+    }
+
+    #[test]
+    fn repeated_words_counted() {
+        assert_eq!(repeated_word_count(DEV_PHRASE), 0);
+        assert_eq!(repeated_word_count(HARDHAT_PHRASE), 1);
+        assert_eq!(
+            repeated_word_count("abandon zoo abandon  zoo\tabandon act"),
+            2
+        );
+        assert_eq!(repeated_word_count(""), 0);
+    }
+
+    #[test]
+    fn seed_name_suggested() {
+        assert_eq!(suggest_seed_name(&[]), "Account 1");
+        let existing = [
+            String::from("Account 1"),
+            String::from("Account 3"),
+            String::from("Alice"),
+        ];
+        assert_eq!(suggest_seed_name(&existing), "Account 2");
+    }
+
+    /// Address created for `derivation_path` of [`DEV_PHRASE`], without network
+    fn dev_address(derivation_path: &str) -> AddressDetails {
+        create_address(
+            None,
             CreateAddressPayload::SeedPhrase {
-                derivation_path: "//Alice",
-                seed_phrase: seed_phrase.clone(),
+                derivation_path,
+                seed_phrase: DEV_PHRASE,
                 seed_name: "Alice",
+                passphrase: None,
             },
         )
+        .unwrap()
+        .into_parts()
+        .0
+    }
+
+    #[test]
+    fn composed_and_decomposed_paths_match() {
+        let composed = dev_address("//caf\u{e9}");
+        let decomposed = dev_address("//cafe\u{301}");
+        assert_eq!(composed, decomposed);
+        assert_eq!(composed.path, "//cafe\u{301}");
+        assert_ne!(composed.public_key, dev_address("//cafe").public_key);
+    }
+
+    #[test]
+    fn derivation_time_estimated() {
+        assert_eq!(estimate_derivation_ms(0, Encryption::Sr25519), 0);
+        assert_eq!(estimate_derivation_ms(1, Encryption::Ed25519), 4);
+        assert_eq!(estimate_derivation_ms(20, Encryption::Sr25519), 100);
+        assert_eq!(
+            estimate_derivation_ms(3, Encryption::Ethereum),
+            estimate_derivation_ms(3, Encryption::Ecdsa)
+        );
+        assert_eq!(
+            estimate_derivation_ms(usize::MAX, Encryption::Ecdsa),
+            u64::MAX.div_ceil(1_000)
+        );
+    }
+
+    /// Measure the per-derivation cost used in [`derivation_cost_us`]
+    #[test]
+    #[ignore = "timing, run in release mode on the target device"]
+    fn derivation_cost_measured() {
+        const ROUNDS: u32 = 50;
+        for encryption in [
+            Encryption::Ed25519,
+            Encryption::Sr25519,
+            Encryption::Ecdsa,
+            Encryption::Ethereum,
+        ] {
+            let start = std::time::Instant::now();
+            for index in 0..ROUNDS {
+                create_address_with_seed_phrase(
+                    None,
+                    encryption,
+                    &format!("//{index}"),
+                    DEV_PHRASE,
+                    "Alice",
+                    None,
+                    false,
+                )
+                .unwrap();
+            }
+            let measured_us = start.elapsed().as_micros() / u128::from(ROUNDS);
+            println!(
+                "{encryption:?}: measured {measured_us} us, estimated {} us",
+                derivation_cost_us(encryption)
+            );
+        }
+    }
+
+    #[test]
+    fn missing_word_recovered() {
+        let alice =
+            full_address_to_multisigner(DEV_PHRASE, "//Alice", None, Encryption::Sr25519).unwrap();
+        let words: Vec<&str> = DEV_PHRASE.split(' ').collect();
+        for missing_index in [3, 11] {
+            let mut partial = words.clone();
+            partial.remove(missing_index);
+            assert_eq!(
+                recover_missing_word(
+                    &partial,
+                    missing_index,
+                    &alice,
+                    "//Alice",
+                    Encryption::Sr25519
+                )
+                .unwrap()
+                .as_deref(),
+                Some(words[missing_index])
+            );
+        }
+        let partial = &words[..11];
+        assert_eq!(
+            recover_missing_word(partial, 11, &alice, "//Bob", Encryption::Sr25519).unwrap(),
+            None
+        );
+        assert!(matches!(
+            recover_missing_word(partial, 12, &alice, "//Alice", Encryption::Sr25519),
+            Err(IdentityError::MissingWordIndex(12))
+        ));
+        assert!(matches!(
+            recover_missing_word(&words[..10], 10, &alice, "//Alice", Encryption::Sr25519),
+            Err(IdentityError::InvalidWordCount(11))
+        ));
+    }
+
+    #[test]
+    fn soft_junctions_found() {
+        for path in ["/soft", "//hard/soft", "/soft//hard", "//a//b/c///password"] {
+            assert!(has_soft_junctions(path), "{path}");
+        }
+        for path in ["", "//hard", "//a//b", "//a///pass/word", "///password"] {
+            assert!(!has_soft_junctions(path), "{path}");
+        }
+    }
+
+    #[test]
+    fn record_only_with_network() {
+        let westend = NetworkSpecs::westend();
+        let outcome = create_address_with_seed_phrase(
+            Some(&westend),
+            Encryption::Sr25519,
+            "//Alice",
+            DEV_PHRASE,
+            "Alice",
+            None,
+            false,
+        )
+        .unwrap();
+        let details = outcome.details().to_owned();
+        assert!(details.network_id.is_some());
+        let (into_details, record) = outcome.into_parts();
+        assert_eq!(into_details, details);
+        let record = record.unwrap();
+        assert_eq!(record.network_genesis_hash, westend.genesis_hash);
+        assert_eq!(record.public_key, details.public_key);
+
+        let outcome = create_address_with_seed_phrase(
+            None,
+            Encryption::Sr25519,
+            "//Alice",
+            DEV_PHRASE,
+            "Alice",
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(matches!(
+            &outcome,
+            AddressOutcome::NetworkAgnostic { details } if details.network_id.is_none()
+        ));
+        assert_eq!(outcome.into_parts().1, None);
+    }
+
+    #[test]
+    fn auth_challenge_domain_separated() {
+        let nonce = [0xde, 0xad, 0xbe, 0xef];
+        let signature = sign_auth_challenge(
+            DEV_PHRASE,
+            "//Alice",
+            None,
+            Encryption::Ed25519,
+            "app.example.com",
+            &nonce,
+        )
+        .unwrap();
+        let expected = sign_message(
+            DEV_PHRASE,
+            "//Alice",
+            None,
+            Encryption::Ed25519,
+            b"<Bytes>app.example.com:deadbeef</Bytes>",
+        )
         .unwrap();
+        assert_eq!(signature, expected);
+        let other_domain = sign_auth_challenge(
+            DEV_PHRASE,
+            "//Alice",
+            None,
+            Encryption::Ed25519,
+            "evil.example.com",
+            &nonce,
+        )
+        .unwrap();
+        assert_ne!(signature, other_domain);
+        let alice =
+            full_address_to_multisigner(DEV_PHRASE, "//Alice", None, Encryption::Ed25519).unwrap();
+        assert!(helpers::verify_signature(
+            &alice,
+            b"<Bytes>app.example.com:deadbeef</Bytes>",
+            &signature
+        ));
+    }
 
-        println!("{:?}", address_details);
-        println!("{:?}", identity_record);
+    #[test]
+    fn network_encryption_overridden() {
+        let westend = NetworkSpecs::westend();
+        let payload = CreateAddressPayload::SeedPhrase {
+            derivation_path: "//Alice",
+            seed_phrase: DEV_PHRASE,
+            seed_name: "Alice",
+            passphrase: None,
+        };
+        let (details, record) =
+            create_address_with_encryption(Some(&westend), payload, Encryption::Ed25519, &[])
+                .unwrap()
+                .into_parts();
+        assert_eq!(details.encryption, Encryption::Ed25519);
+        assert_eq!(
+            details.network_id,
+            Some(NetworkSpecsKey::from_parts(&westend.genesis_hash, &Encryption::Ed25519).unwrap())
+        );
+        let ed25519 =
+            full_address_to_multisigner(DEV_PHRASE, "//Alice", None, Encryption::Ed25519).unwrap();
+        assert_eq!(details.public_key, multisigner_to_public(&ed25519));
+        let record = record.unwrap();
+        assert_eq!(record.encryption, Encryption::Ed25519);
+        assert_eq!(record.network_genesis_hash, westend.genesis_hash);
+    }
+
+    #[test]
+    fn phrase_strength_parsed() {
+        for (words_number, strength) in [
+            (12, PhraseStrength::Words12),
+            (15, PhraseStrength::Words15),
+            (18, PhraseStrength::Words18),
+            (21, PhraseStrength::Words21),
+            (24, PhraseStrength::Words24),
+        ] {
+            assert_eq!(PhraseStrength::try_from(words_number).unwrap(), strength);
+            assert_eq!(
+                PhraseStrength::from_str(&format!(" {words_number}\n")).unwrap(),
+                strength
+            );
+            assert_eq!(
+                generate_phrase(strength).split(' ').count(),
+                words_number as usize
+            );
+        }
+        for words_number in [0, 11, 13, 25] {
+            assert!(matches!(
+                PhraseStrength::try_from(words_number),
+                Err(IdentityError::InvalidWordCount(n)) if n == words_number
+            ));
+        }
+        for input in ["twelve", "", "-12", "12.0"] {
+            assert!(matches!(
+                PhraseStrength::from_str(input),
+                Err(IdentityError::InvalidWordCount(0))
+            ));
+        }
+    }
+
+    #[test]
+    fn prehashed_signing_only_for_ecdsa() {
+        let digest = [0x42; 32];
+        for encryption in [Encryption::Ed25519, Encryption::Sr25519] {
+            assert!(matches!(
+                sign_prehashed(DEV_PHRASE, "//Alice", None, encryption, &digest),
+                Err(IdentityError::PrehashedSigningNotSupported(e)) if e == encryption
+            ));
+        }
+        let signature =
+            sign_prehashed(DEV_PHRASE, "//Alice", None, Encryption::Ecdsa, &digest).unwrap();
+        let MultiSignature::Ecdsa(signature) = signature else {
+            panic!("expected ecdsa signature, got {signature:?}");
+        };
+        assert_eq!(
+            MultiSigner::Ecdsa(signature.recover_prehashed(&digest).unwrap()),
+            full_address_to_multisigner(DEV_PHRASE, "//Alice", None, Encryption::Ecdsa).unwrap()
+        );
+        let signature = sign_prehashed(
+            HARDHAT_PHRASE,
+            "m/44'/60'/0'/0/0",
+            None,
+            Encryption::Ethereum,
+            &digest,
+        )
+        .unwrap();
+        assert_eq!(ecdsa_signer(&signature, &digest), HARDHAT_ACCOUNT);
+    }
+
+    #[test]
+    fn address_from_secret_uri() {
+        let westend = NetworkSpecs::westend();
+        let alice =
+            full_address_to_multisigner(DEV_PHRASE, "//Alice", None, Encryption::Sr25519).unwrap();
+        let outcome =
+            create_address_from_uri(Some(&westend), &format!("{DEV_PHRASE}//Alice"), "Alice")
+                .unwrap();
+        assert!(matches!(outcome, AddressOutcome::WithNetwork { .. }));
+        let details = outcome.details();
+        assert_eq!(details.path, "//Alice");
+        assert!(!details.has_pwd);
+        assert_eq!(details.public_key, multisigner_to_public(&alice));
+
+        let outcome = create_address_from_uri(
+            Some(&westend),
+            &format!("{DEV_PHRASE}//Alice///password"),
+            "Alice",
+        )
+        .unwrap();
+        let details = outcome.details();
+        assert_eq!(details.path, "//Alice");
+        assert!(details.has_pwd);
+        assert_ne!(details.public_key, multisigner_to_public(&alice));
+
+        let outcome = create_address_from_uri(None, DEV_PHRASE, "Alice").unwrap();
+        assert!(matches!(outcome, AddressOutcome::NetworkAgnostic { .. }));
+        let details = outcome.details();
+        assert_eq!(details.path, "");
+        assert_eq!(hex::encode(&details.public_key), DEV_ROOT_SR25519);
+    }
+
+    #[test]
+    fn invalid_word_count_not_panicking() {
+        for words_number in [0, 1, 13, 23, 48] {
+            assert!(matches!(
+                generate_phrase_with_indices(words_number),
+                Err(IdentityError::InvalidWordCount(n)) if n == words_number
+            ));
+            assert!(matches!(
+                generate_random_phrase(words_number),
+                Err(IdentityError::InvalidWordCount(n)) if n == words_number
+            ));
+        }
+        let (phrase, indices) = generate_phrase_with_indices(24).unwrap();
+        assert_eq!(phrase.split(' ').count(), 24);
+        assert_eq!(indices.len(), 24);
+    }
+
+    #[test]
+    fn seed_phrase_validated() {
+        validate_seed_phrase(DEV_PHRASE).unwrap();
+        validate_seed_phrase(&format!("{} about", ["abandon"; 11].join(" "))).unwrap();
+        for invalid in [
+            ["abandon"; 12].join(" "),
+            ["abandon"; 11].join(" "),
+            DEV_PHRASE.replace("walk", "walkk"),
+            format!("{DEV_PHRASE} walk"),
+            String::new(),
+        ] {
+            assert!(
+                matches!(
+                    validate_seed_phrase(&invalid),
+                    Err(IdentityError::InvalidMnemonic)
+                ),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn passphrase_matches_password() {
+        let westend = NetworkSpecs::westend();
+        let with_passphrase = |derivation_path: &str| {
+            create_address(
+                Some(&westend),
+                CreateAddressPayload::SeedPhrase {
+                    derivation_path,
+                    seed_phrase: DEV_PHRASE,
+                    seed_name: "Alice",
+                    passphrase: Some(String::from("secret")),
+                },
+            )
+            .unwrap()
+            .into_parts()
+            .0
+        };
+        let (with_password, _) = create_address_from_uri(
+            Some(&westend),
+            &format!("{DEV_PHRASE}//Alice///secret"),
+            "Alice",
+        )
+        .unwrap()
+        .into_parts();
+        for derivation_path in ["//Alice", "//Alice///other"] {
+            let details = with_passphrase(derivation_path);
+            assert_eq!(details.path, "//Alice");
+            assert!(details.has_pwd);
+            assert_eq!(details.public_key, with_password.public_key);
+        }
+        assert_ne!(with_password.public_key, dev_address("//Alice").public_key);
+    }
+
+    #[test]
+    fn personal_sign_recovers_hardhat_account() {
+        assert_eq!(
+            hex::encode(eth_message_hash(b"hello")),
+            "50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"
+        );
+        let message = b"hello";
+        let signature =
+            eth_personal_sign(HARDHAT_PHRASE, "m/44'/60'/0'/0/0", None, message).unwrap();
+        assert!(matches!(signature[64], 27 | 28));
+        let signer = helpers::eth_recover(message, &signature).unwrap();
+        assert_eq!(hex::encode(signer), HARDHAT_ACCOUNT);
+    }
+
+    #[test]
+    fn eth_recover_accepts_both_v_forms() {
+        let message = b"recover me";
+        let mut signature =
+            eth_personal_sign(HARDHAT_PHRASE, "m/44'/60'/0'/0/0", None, message).unwrap();
+        signature[64] -= 27;
+        assert_eq!(
+            hex::encode(helpers::eth_recover(message, &signature).unwrap()),
+            HARDHAT_ACCOUNT
+        );
+        assert_ne!(
+            hex::encode(helpers::eth_recover(b"other message", &signature).unwrap()),
+            HARDHAT_ACCOUNT
+        );
+        signature[64] = 31;
+        assert!(helpers::eth_recover(message, &signature).is_err());
+    }
+
+    #[test]
+    fn pasted_phrase_normalized() {
+        let pasted =
+            "  Bottom DRIVE obey\tlake\ncurtain  smoke\r\nbasket hold race lonely fit Walk \n";
+        assert_eq!(normalize_phrase(pasted), DEV_PHRASE);
+        validate_seed_phrase(&normalize_phrase(pasted)).unwrap();
+        assert_eq!(normalize_phrase(DEV_PHRASE), DEV_PHRASE);
+        assert_eq!(
+            normalize_phrase("あいこくしん\u{3000}あいさつ"),
+            "あいこくしん あいさつ"
+        );
+        assert_eq!(normalize_phrase(" \t\n"), "");
+    }
+
+    #[test]
+    fn email_account_deterministic() {
+        let seed_phrase =
+            seed_phrase_from_email_and_password("alice@example.com", "correct horse battery")
+                .unwrap();
+        assert_eq!(
+            hex::encode(entropy_from_phrase(&seed_phrase).unwrap()),
+            "4b321a1ef7327c43769174d9c1f6ad49e9481b4011c4a219fd7c0e8aa2865305"
+        );
+        assert_eq!(
+            &*seed_phrase_from_email_and_password(" Alice@Example.COM\n", "correct horse battery")
+                .unwrap(),
+            &*seed_phrase
+        );
+        assert_ne!(
+            &*seed_phrase_from_email_and_password("bob@example.com", "correct horse battery")
+                .unwrap(),
+            &*seed_phrase
+        );
+        assert!(matches!(
+            seed_phrase_from_email_and_password("  ", "correct horse battery"),
+            Err(IdentityError::EmptyEmail)
+        ));
+        assert!(matches!(
+            seed_phrase_from_email_and_password("alice@example.com", "short"),
+            Err(IdentityError::PasswordTooShort(8))
+        ));
+    }
+
+    #[test]
+    fn derivation_path_parsed() {
+        let info = parse_derivation_path("//polkadot/soft//0///password").unwrap();
+        assert_eq!(
+            info,
+            DerivationInfo {
+                cropped_path: String::from("//polkadot/soft//0"),
+                has_pwd: true,
+                junctions: vec![
+                    Junction::Hard(String::from("polkadot")),
+                    Junction::Soft(String::from("soft")),
+                    Junction::Hard(String::from("0")),
+                ],
+            }
+        );
+        let info = parse_derivation_path("").unwrap();
+        assert_eq!(info.cropped_path, "");
+        assert!(!info.has_pwd);
+        assert!(info.junctions.is_empty());
+        let info = parse_derivation_path("///password").unwrap();
+        assert!(info.has_pwd);
+        assert!(info.junctions.is_empty());
+        assert!(matches!(
+            parse_derivation_path("Alice"),
+            Err(IdentityError::InvalidDerivationPath)
+        ));
+    }
+
+    #[test]
+    fn next_index_per_seed() {
+        let address = |seed_name: &str, path: &str| AddressDetails {
+            seed_name: String::from(seed_name),
+            path: String::from(path),
+            ..dev_address("")
+        };
+        let addresses = [
+            address("Alice", "//polkadot//3"),
+            address("Alice", "//0"),
+            address("Alice", "//7/soft"),
+            address("Bob", "//polkadot"),
+            address("Bob", "//kusama//1///password"),
+            address("Carol", "//polkadot//4294967295"),
+        ];
+        let next_indices = next_indices_per_seed(&addresses);
+        assert_eq!(
+            next_indices,
+            BTreeMap::from([
+                (String::from("Alice"), Some(4)),
+                (String::from("Bob"), Some(2)),
+                (String::from("Carol"), None),
+            ])
+        );
+        // exhausted seed stays exhausted
+        let addresses = [
+            address("Carol", "//polkadot//4294967295"),
+            address("Carol", "//polkadot//5"),
+            address("Dave", "//4294967294"),
+        ];
+        assert_eq!(
+            next_indices_per_seed(&addresses),
+            BTreeMap::from([
+                (String::from("Carol"), None),
+                (String::from("Dave"), Some(u32::MAX)),
+            ])
+        );
+    }
+
+    #[test]
+    fn entropy_phrase_round_trip() {
+        let zero_phrase = format!("{} about", ["abandon"; 11].join(" "));
+        assert_eq!(&*phrase_from_entropy(&[0; 16]).unwrap(), zero_phrase);
+        assert_eq!(entropy_from_phrase(&zero_phrase).unwrap(), [0; 16]);
+        let max_phrase = format!("{} wrong", ["zoo"; 11].join(" "));
+        assert_eq!(&*phrase_from_entropy(&[0xff; 16]).unwrap(), max_phrase);
+        assert_eq!(
+            &*phrase_from_entropy(&[0; 32]).unwrap(),
+            format!("{} art", ["abandon"; 23].join(" "))
+        );
+        let entropy = entropy_from_phrase(DEV_PHRASE).unwrap();
+        assert_eq!(entropy.len(), 16);
+        assert_eq!(&*phrase_from_entropy(&entropy).unwrap(), DEV_PHRASE);
+        for length in [0, 15, 17, 33] {
+            assert!(matches!(
+                phrase_from_entropy(&vec![0; length]),
+                Err(IdentityError::InvalidEntropyLength(l)) if l == length
+            ));
+        }
+        assert!(matches!(
+            entropy_from_phrase(&["abandon"; 12].join(" ")),
+            Err(IdentityError::InvalidMnemonic)
+        ));
+    }
+
+    #[test]
+    fn long_password_rejected() {
+        let too_long = format!("//Alice///{}", "a".repeat(MAX_PASSWORD_LEN + 1));
+        assert!(matches!(
+            parse_derivation_path(&too_long),
+            Err(IdentityError::PasswordTooLong(MAX_PASSWORD_LEN))
+        ));
+        assert!(matches!(
+            create_address_with_seed_phrase(
+                None,
+                Encryption::Sr25519,
+                &too_long,
+                DEV_PHRASE,
+                "Alice",
+                None,
+                false,
+            ),
+            Err(IdentityError::PasswordTooLong(MAX_PASSWORD_LEN))
+        ));
+        let longest = format!("//Alice///{}", "é".repeat(MAX_PASSWORD_LEN));
+        assert!(parse_derivation_path(&longest).unwrap().has_pwd);
+        let (details, _) = create_address_with_seed_phrase(
+            None,
+            Encryption::Sr25519,
+            &longest,
+            DEV_PHRASE,
+            "Alice",
+            None,
+            false,
+        )
+        .unwrap()
+        .into_parts();
+        assert!(details.has_pwd);
+    }
+
+    #[test]
+    fn root_key_signing() {
+        let message = b"I own this seed";
+        let signature = sign_with_root_key(DEV_PHRASE, None, Encryption::Sr25519, message).unwrap();
+        let root = dev_address("");
+        assert_eq!(hex::encode(&root.public_key), DEV_ROOT_SR25519);
+        let root_signer = helpers::get_multisigner(&root.public_key, &Encryption::Sr25519).unwrap();
+        assert!(helpers::verify_signature(&root_signer, message, &signature));
+        let alice =
+            full_address_to_multisigner(DEV_PHRASE, "//Alice", None, Encryption::Sr25519).unwrap();
+        assert!(!helpers::verify_signature(&alice, message, &signature));
+        assert_eq!(
+            sign_with_root_key(DEV_PHRASE, None, Encryption::Ed25519, message).unwrap(),
+            sign_message(DEV_PHRASE, "", None, Encryption::Ed25519, message).unwrap()
+        );
+        assert!(matches!(
+            sign_with_root_key("", None, Encryption::Sr25519, message),
+            Err(IdentityError::EmptySeed)
+        ));
+    }
+
+    #[test]
+    fn public_key_stored_in_details() {
+        for (encryption, length) in [
+            (Encryption::Ed25519, 32),
+            (Encryption::Sr25519, 32),
+            (Encryption::Ecdsa, 33),
+            (Encryption::Ethereum, 33),
+        ] {
+            let specs = NetworkSpecs {
+                encryption,
+                ..NetworkSpecs::westend()
+            };
+            let (details, record) = create_address(
+                Some(&specs),
+                CreateAddressPayload::SeedPhrase {
+                    derivation_path: "//Alice",
+                    seed_phrase: DEV_PHRASE,
+                    seed_name: "Alice",
+                    passphrase: None,
+                },
+            )
+            .unwrap()
+            .into_parts();
+            let multisigner =
+                full_address_to_multisigner(DEV_PHRASE, "//Alice", None, encryption).unwrap();
+            assert_eq!(details.public_key, multisigner_to_public(&multisigner));
+            assert_eq!(details.public_key.len(), length);
+            assert_eq!(record.unwrap().public_key, details.public_key);
+        }
+    }
+
+    #[test]
+    fn batch_matches_single_derivation() {
+        let westend = NetworkSpecs::westend();
+        let paths = ["", "//Alice", "//0/soft", "//Alice///password"];
+        let batch =
+            create_addresses_batch(Some(&westend), DEV_PHRASE, "Alice", &paths, &[]).unwrap();
+        assert_eq!(batch.len(), paths.len());
+        for (path, batch_address) in paths.iter().zip(batch) {
+            let single = create_address(
+                Some(&westend),
+                CreateAddressPayload::SeedPhrase {
+                    derivation_path: path,
+                    seed_phrase: DEV_PHRASE,
+                    seed_name: "Alice",
+                    passphrase: None,
+                },
+            )
+            .unwrap();
+            assert_eq!(batch_address, single, "{path}");
+        }
+        assert!(matches!(
+            create_addresses_batch(Some(&westend), "", "Alice", &paths, &[]),
+            Err(IdentityError::EmptySeed)
+        ));
+    }
+
+    #[test]
+    fn secret_exposed_inherited_from_seed() {
+        let westend = NetworkSpecs::westend();
+        let exposed = AddressDetails {
+            secret_exposed: true,
+            ..dev_address("//0")
+        };
+        let others_exposed = AddressDetails {
+            seed_name: String::from("Bob"),
+            ..exposed.clone()
+        };
+        let outcome = create_address_for_seed(
+            Some(&westend),
+            "//1",
+            DEV_PHRASE,
+            "Alice",
+            &[dev_address("//2"), exposed],
+        )
+        .unwrap();
+        assert!(outcome.details().secret_exposed);
+        let outcome = create_address_for_seed(
+            Some(&westend),
+            "//1",
+            DEV_PHRASE,
+            "Alice",
+            &[dev_address("//2"), others_exposed],
+        )
+        .unwrap();
+        assert!(!outcome.details().secret_exposed);
+        let outcome =
+            create_address_for_seed(Some(&westend), "//1", DEV_PHRASE, "Alice", &[]).unwrap();
+        assert!(!outcome.details().secret_exposed);
+    }
+
+    #[test]
+    fn phrases_in_other_languages() {
+        for language in [Language::French, Language::Japanese, Language::Spanish] {
+            let phrase = generate_random_phrase_in(18, language).unwrap();
+            assert_eq!(phrase.split_whitespace().count(), 18);
+            validate_seed_phrase_in(&phrase, language).unwrap();
+            assert!(matches!(
+                validate_seed_phrase(&phrase),
+                Err(IdentityError::InvalidMnemonic)
+            ));
+            let english = to_english_phrase(&phrase, language).unwrap();
+            validate_seed_phrase(&english).unwrap();
+            assert!(matches!(
+                generate_random_phrase_in(16, language),
+                Err(IdentityError::InvalidWordCount(16))
+            ));
+        }
+        assert!(matches!(
+            validate_seed_phrase_in(DEV_PHRASE, Language::French),
+            Err(IdentityError::InvalidMnemonic)
+        ));
+    }
+
+    #[test]
+    fn with_seed_passes_result_through() {
+        let phrase = SecretString::new(DEV_PHRASE.to_string());
+        let outcome = with_seed(phrase, |seed_phrase| {
+            assert_eq!(seed_phrase, DEV_PHRASE);
+            create_address_with_seed_phrase(
+                None,
+                Encryption::Sr25519,
+                "//Alice",
+                seed_phrase,
+                "Alice",
+                None,
+                false,
+            )
+        })
+        .unwrap();
+        assert_eq!(
+            hex::encode(&outcome.details().public_key),
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+        let words = with_seed(SecretString::new(DEV_PHRASE.to_string()), |seed_phrase| {
+            seed_phrase.split(' ').count()
+        });
+        assert_eq!(words, 12);
+    }
+
+    #[test]
+    fn multilingual_demo_runs() {
+        multilingual_demo();
+        assert_eq!(
+            demo_alice_address(DEV_PHRASE, Language::English),
+            ALICE_SS58
+        );
+    }
+
+    #[test]
+    fn secret_exposed_inherited_in_batch() {
+        let westend = NetworkSpecs::westend();
+        let exposed = AddressDetails {
+            secret_exposed: true,
+            ..dev_address("//0")
+        };
+        let others_exposed = AddressDetails {
+            seed_name: String::from("Bob"),
+            ..exposed.clone()
+        };
+        // root key derivation, and derivation from the phrase for password
+        let paths = ["//1", "/soft", "//2///password"];
+        for (existing, expected) in [(&exposed, true), (&others_exposed, false)] {
+            let existing = [dev_address("//3"), existing.clone()];
+            let batch =
+                create_addresses_batch(Some(&westend), DEV_PHRASE, "Alice", &paths, &existing)
+                    .unwrap();
+            assert_eq!(batch.len(), paths.len());
+            for outcome in batch {
+                let details = outcome.details();
+                assert_eq!(details.secret_exposed, expected, "{}", details.path);
+            }
+            let accounts = create_accounts(
+                DerivationStrategy::PerNetwork,
+                DEV_PHRASE,
+                "Alice",
+                &[westend.clone(), NetworkSpecs::polkadot()],
+                &existing,
+            )
+            .unwrap();
+            assert!(accounts
+                .iter()
+                .all(|details| details.secret_exposed == expected));
+            let outcome = create_address_with_encryption(
+                Some(&westend),
+                CreateAddressPayload::SeedPhrase {
+                    derivation_path: "//1",
+                    seed_phrase: DEV_PHRASE,
+                    seed_name: "Alice",
+                    passphrase: None,
+                },
+                Encryption::Ed25519,
+                &existing,
+            )
+            .unwrap();
+            assert_eq!(outcome.details().secret_exposed, expected);
+        }
     }
 }
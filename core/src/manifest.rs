@@ -0,0 +1,148 @@
+//! Wallet manifest, non-secret backup of the whole wallet layout
+//!
+//! Manifest contains all [`NetworkSpecs`] and all addresses, as
+//! [`AddressKey`] with [`AddressDetails`], and no secrets: on a new device
+//! the keys are restored from the seed phrases, and the manifest restores
+//! networks and the derivations made for each seed.
+//!
+//! Manifest layout is:
+//!
+//! - `u8` format version, currently `1`
+//! - SCALE-encoded `(Vec<NetworkSpecs>, Vec<(AddressKey, AddressDetails)>)`
+//! - 32-byte `blake2_256` checksum of everything above
+use codec::{Decode, Encode};
+use sp_core::hashing::blake2_256;
+
+use crate::{
+    error::{DefinitionError, DefinitionResult},
+    keyring::AddressKey,
+    network_spec::NetworkSpecs,
+    users::AddressDetails,
+};
+
+/// Current manifest format version
+const MANIFEST_VERSION: u8 = 1;
+
+/// Length of the manifest checksum
+const CHECKSUM_LEN: usize = 32;
+
+/// Export networks and accounts into versioned checksummed manifest
+pub fn export_manifest(
+    networks: &[NetworkSpecs],
+    accounts: &[(AddressKey, AddressDetails)],
+) -> Vec<u8> {
+    let mut manifest = vec![MANIFEST_VERSION];
+    (networks, accounts).encode_to(&mut manifest);
+    let checksum = blake2_256(&manifest);
+    manifest.extend_from_slice(&checksum);
+    manifest
+}
+
+/// Import networks and accounts from the manifest made by
+/// [`export_manifest`]
+///
+/// Checksum is verified before anything is decoded, so damaged manifest is
+/// reported as such, and not as a decoding error. Decoded entries are
+/// checked with [`NetworkSpecs::validate`] and [`AddressDetails::validate`],
+/// checksum only proves the manifest was not damaged, not that it was made
+/// by a correct exporter. Account failing the check results in
+/// [`DefinitionError::InvalidManifestAccount`] with its position.
+pub fn import_manifest(
+    bytes: &[u8],
+) -> DefinitionResult<(Vec<NetworkSpecs>, Vec<(AddressKey, AddressDetails)>)> {
+    if bytes.len() < 1 + CHECKSUM_LEN {
+        return Err(DefinitionError::ManifestTooShort);
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    if blake2_256(content) != checksum {
+        return Err(DefinitionError::ManifestChecksumMismatch);
+    }
+    let (version, mut encoded) = (content[0], &content[1..]);
+    if version != MANIFEST_VERSION {
+        return Err(DefinitionError::UnsupportedManifestVersion(version));
+    }
+    let (networks, accounts) =
+        <(Vec<NetworkSpecs>, Vec<(AddressKey, AddressDetails)>)>::decode(&mut encoded)?;
+    for specs in &networks {
+        specs.validate()?;
+    }
+    for (position, (_, address_details)) in accounts.iter().enumerate() {
+        address_details
+            .validate()
+            .map_err(|_| DefinitionError::InvalidManifestAccount(position))?;
+    }
+    Ok((networks, accounts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sp_core::sr25519;
+    use sp_runtime::MultiSigner;
+
+    use crate::{crypto::Encryption, network_spec::NetworkSpecsKey};
+
+    fn account(encryption: Encryption) -> (AddressKey, AddressDetails) {
+        let polkadot = NetworkSpecs::polkadot();
+        let multisigner = MultiSigner::Sr25519(sr25519::Public::from_raw([1; 32]));
+        let address_details = AddressDetails {
+            seed_name: String::from("Alice"),
+            path: String::from("//polkadot"),
+            has_pwd: false,
+            network_id: Some(
+                NetworkSpecsKey::from_parts(&polkadot.genesis_hash, &polkadot.encryption).unwrap(),
+            ),
+            encryption,
+            public_key: vec![1; 32],
+            secret_exposed: false,
+        };
+        (
+            AddressKey::new(multisigner, Some(polkadot.genesis_hash)),
+            address_details,
+        )
+    }
+
+    #[test]
+    fn manifest_round_trip() {
+        let networks = vec![NetworkSpecs::polkadot(), NetworkSpecs::westend()];
+        let accounts = vec![account(Encryption::Sr25519)];
+        let manifest = export_manifest(&networks, &accounts);
+        assert_eq!(manifest[0], MANIFEST_VERSION);
+        assert_eq!(import_manifest(&manifest).unwrap(), (networks, accounts));
+    }
+
+    #[test]
+    fn manifest_damage_rejected() {
+        let mut manifest = export_manifest(&[NetworkSpecs::polkadot()], &[]);
+        assert!(matches!(
+            import_manifest(&manifest[..CHECKSUM_LEN]),
+            Err(DefinitionError::ManifestTooShort)
+        ));
+        manifest[1] ^= 1;
+        assert!(matches!(
+            import_manifest(&manifest),
+            Err(DefinitionError::ManifestChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn manifest_invalid_entries_rejected() {
+        let mut specs = NetworkSpecs::polkadot();
+        specs.unit = String::new();
+        let manifest = export_manifest(&[specs], &[]);
+        assert!(matches!(
+            import_manifest(&manifest),
+            Err(DefinitionError::EmptyNetworkUnit)
+        ));
+
+        let manifest = export_manifest(
+            &[NetworkSpecs::polkadot()],
+            &[account(Encryption::Sr25519), account(Encryption::Ed25519)],
+        );
+        assert!(matches!(
+            import_manifest(&manifest),
+            Err(DefinitionError::InvalidManifestAccount(1))
+        ));
+    }
+}
@@ -1,12 +1,18 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use codec::{Decode, Encode};
-use sp_core::H256;
+use sp_core::{crypto::Ss58AddressFormat, H256};
 
-use crate::{definitions::Encryption, error::DefinitionResult, helpers::unhex};
+use crate::{
+    crypto::Encryption,
+    error::{DefinitionError, DefinitionResult},
+    helpers::unhex,
+    users::AddressDetails,
+};
 
 /// These network parameters are sufficient to add network into Vault database.
 #[derive(Decode, Encode, PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkSpecs {
     /// Network-specific prefix for address representation in
     /// [base58 format](https://docs.rs/sp-core/6.0.0/sp_core/crypto/trait.Ss58Codec.html#method.to_ss58check_with_version)  
@@ -61,6 +67,9 @@ pub struct NetworkSpecs {
 #[derive(Decode, Hash, Encode, PartialEq, Eq, Debug, Clone)]
 pub struct NetworkSpecsKey(Vec<u8>);
 
+/// Length of encoded `NetworkSpecsKey` content: variant byte and genesis hash
+const NETWORK_SPECS_KEY_LEN: usize = 33;
+
 /// Decoded `NetworkSpecsKey` content, encryption-based variants with vector
 /// genesis hash inside
 #[derive(Decode, Encode)]
@@ -74,28 +83,43 @@ enum NetworkSpecsKeyContent {
 impl NetworkSpecsKey {
     /// Generate [`NetworkSpecsKey`] from parts: network genesis hash and
     /// [`Encryption`]
-    pub fn from_parts(genesis_hash: &H256, encryption: &Encryption) -> Self {
+    ///
+    /// All-zero genesis hash is rejected: it is never a real network genesis
+    /// hash, and is either a bug or a malicious placeholder.
+    pub fn from_parts(genesis_hash: &H256, encryption: &Encryption) -> DefinitionResult<Self> {
+        if genesis_hash.is_zero() {
+            return Err(DefinitionError::ZeroGenesisHash);
+        }
         let network_key_content = match encryption {
             Encryption::Ed25519 => NetworkSpecsKeyContent::Ed25519(*genesis_hash),
             Encryption::Sr25519 => NetworkSpecsKeyContent::Sr25519(*genesis_hash),
             Encryption::Ecdsa => NetworkSpecsKeyContent::Ecdsa(*genesis_hash),
             Encryption::Ethereum => NetworkSpecsKeyContent::Ethereum(*genesis_hash),
         };
-        Self(network_key_content.encode())
+        Ok(Self(network_key_content.encode()))
     }
 
     /// Transform hexadecimal `String` into [`NetworkSpecsKey`]  
     ///
     /// Vault receives hexadecimal strings from user interface.
     ///
-    /// This function checks only that hexadecimal format is valid, no check
-    /// of encryption validity is done here.  
+    /// Decoded key must be a valid [`NetworkSpecsKey`], i.e. one of the
+    /// encryption variants with 32-byte genesis hash, so that no garbage keys
+    /// get into the database. Key bytes are kept as is.  
     pub fn from_hex(hex_line: &str) -> DefinitionResult<Self> {
-        Ok(Self(unhex(hex_line)?))
+        let network_specs_key = Self(unhex(hex_line)?);
+        network_specs_key.genesis_hash_encryption()?;
+        Ok(network_specs_key)
     }
 
     /// Get genesis hash as `H256` and [`Encryption`] from [`NetworkSpecsKey`]
+    ///
+    /// Length is checked before decoding: valid key is always one variant
+    /// byte and 32-byte genesis hash.
     pub fn genesis_hash_encryption(&self) -> DefinitionResult<(H256, Encryption)> {
+        if self.0.len() != NETWORK_SPECS_KEY_LEN {
+            return Err(DefinitionError::WrongNetworkSpecsKeyLength);
+        }
         match <NetworkSpecsKeyContent>::decode(&mut &self.0[..])? {
             NetworkSpecsKeyContent::Ed25519(b) => Ok((b, Encryption::Ed25519)),
             NetworkSpecsKeyContent::Sr25519(b) => Ok((b, Encryption::Sr25519)),
@@ -110,8 +134,74 @@ impl NetworkSpecsKey {
     }
 }
 
-pub fn default_network_specs() -> [NetworkSpecs; 3] {
-    [
+/// [`NetworkSpecsKey`] is serialized as `0x`-prefixed hexadecimal key
+#[cfg(feature = "serde")]
+impl serde::Serialize for NetworkSpecsKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(&self.0)))
+    }
+}
+
+/// Deserialized key is validated same way as in
+/// [`from_hex`](NetworkSpecsKey::from_hex)
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NetworkSpecsKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex_line = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::from_hex(&hex_line).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Largest base58 prefix, prefixes are 14-bit
+const MAX_BASE58_PREFIX: u16 = 0x3fff;
+
+impl NetworkSpecs {
+    /// Check that network specs could be used, e.g. after the import from QR
+    /// code
+    ///
+    /// Base58 prefix must fit into 14 bits and must not be reserved, name
+    /// and unit must not be empty, genesis hash must not be all zeroes.
+    pub fn validate(&self) -> DefinitionResult<()> {
+        if self.base58prefix > MAX_BASE58_PREFIX
+            || Ss58AddressFormat::custom(self.base58prefix).is_reserved()
+        {
+            return Err(DefinitionError::InvalidBase58Prefix(self.base58prefix));
+        }
+        if self.name.is_empty() {
+            return Err(DefinitionError::EmptyNetworkName);
+        }
+        if self.unit.is_empty() {
+            return Err(DefinitionError::EmptyNetworkUnit);
+        }
+        if self.genesis_hash.is_zero() {
+            return Err(DefinitionError::ZeroGenesisHash);
+        }
+        Ok(())
+    }
+
+    /// Polkadot network specs
+    pub fn polkadot() -> Self {
+        NetworkSpecs {
+            address: String::from("wss://rpc.polkadot.io"),
+            base58prefix: 0,
+            color: String::from("#E6027A"),
+            decimals: 10,
+            encryption: Encryption::Sr25519,
+            genesis_hash: H256::from_str(
+                "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3",
+            )
+            .expect("known value"),
+            logo: String::from("polkadot"),
+            name: String::from("polkadot"),
+            path_id: String::from("//polkadot"),
+            secondary_color: String::from("#262626"),
+            title: String::from("Polkadot"),
+            unit: String::from("DOT"),
+        }
+    }
+
+    /// Kusama network specs
+    pub fn kusama() -> Self {
         NetworkSpecs {
             address: String::from("wss://kusama-rpc.polkadot.io"),
             base58prefix: 2,
@@ -128,40 +218,688 @@ pub fn default_network_specs() -> [NetworkSpecs; 3] {
             secondary_color: String::from("#262626"),
             title: String::from("Kusama"),
             unit: String::from("KSM"),
-        },
+        }
+    }
+
+    /// Westend network specs
+    pub fn westend() -> Self {
         NetworkSpecs {
-            address: String::from("wss://rpc.polkadot.io"),
+            address: String::from("wss://westend-rpc.polkadot.io"),
+            base58prefix: 42,
+            color: String::from("#660D35"),
+            decimals: 12,
+            encryption: Encryption::Sr25519,
+            genesis_hash: H256::from_str(
+                "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+            )
+            .expect("known value"),
+            logo: String::from("westend"),
+            name: String::from("westend"),
+            path_id: String::from("//westend"),
+            secondary_color: String::from("#262626"),
+            title: String::from("Westend"),
+            unit: String::from("WND"),
+        }
+    }
+
+    /// Polkadot Asset Hub network specs
+    pub fn polkadot_asset_hub() -> Self {
+        NetworkSpecs {
+            address: String::from("wss://polkadot-asset-hub-rpc.polkadot.io"),
             base58prefix: 0,
             color: String::from("#E6027A"),
             decimals: 10,
             encryption: Encryption::Sr25519,
             genesis_hash: H256::from_str(
-                "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3",
+                "68d56f15f85d3136970ec16946040bc1752654e906147f7e43e9d539d7c3de2f",
             )
             .expect("known value"),
-            logo: String::from("polkadot"),
-            name: String::from("polkadot"),
-            path_id: String::from("//polkadot"),
+            logo: String::from("statemint"),
+            name: String::from("statemint"),
+            path_id: String::from("//statemint"),
             secondary_color: String::from("#262626"),
-            title: String::from("Polkadot"),
+            title: String::from("Polkadot Asset Hub"),
             unit: String::from("DOT"),
-        },
+        }
+    }
+
+    /// Kusama Asset Hub network specs
+    pub fn kusama_asset_hub() -> Self {
         NetworkSpecs {
-            address: String::from("wss://westend-rpc.polkadot.io"),
-            base58prefix: 42,
-            color: String::from("#660D35"),
+            address: String::from("wss://kusama-asset-hub-rpc.polkadot.io"),
+            base58prefix: 2,
+            color: String::from("#000"),
             decimals: 12,
             encryption: Encryption::Sr25519,
             genesis_hash: H256::from_str(
-                "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+                "48239ef607d7928874027a43a67689209727dfb3d3dc5e5b03a39bdc2eda771a",
             )
             .expect("known value"),
-            logo: String::from("westend"),
-            name: String::from("westend"),
-            path_id: String::from("//westend"),
+            logo: String::from("statemine"),
+            name: String::from("statemine"),
+            path_id: String::from("//statemine"),
             secondary_color: String::from("#262626"),
-            title: String::from("Westend"),
-            unit: String::from("WND"),
-        },
+            title: String::from("Kusama Asset Hub"),
+            unit: String::from("KSM"),
+        }
+    }
+
+    /// Paseo testnet network specs
+    pub fn paseo() -> Self {
+        NetworkSpecs {
+            address: String::from("wss://paseo.rpc.amforc.com"),
+            base58prefix: 0,
+            color: String::from("#4D9F8C"),
+            decimals: 10,
+            encryption: Encryption::Sr25519,
+            genesis_hash: H256::from_str(
+                "77afd6190f1554ad45fd0d31aee62aacc33c6db0ea801129acb813f913e0764f",
+            )
+            .expect("known value"),
+            logo: String::from("paseo"),
+            name: String::from("paseo"),
+            path_id: String::from("//paseo"),
+            secondary_color: String::from("#262626"),
+            title: String::from("Paseo"),
+            unit: String::from("PAS"),
+        }
+    }
+}
+
+/// Network specs of the default networks: Kusama, Polkadot, Westend, Asset
+/// Hubs of Polkadot and Kusama, and Paseo
+pub fn default_network_specs() -> Vec<NetworkSpecs> {
+    vec![
+        NetworkSpecs::kusama(),
+        NetworkSpecs::polkadot(),
+        NetworkSpecs::westend(),
+        NetworkSpecs::polkadot_asset_hub(),
+        NetworkSpecs::kusama_asset_hub(),
+        NetworkSpecs::paseo(),
     ]
 }
+
+/// Find default network specs by genesis hash, e.g. received from a dApp
+pub fn network_specs_by_genesis(hash: &H256) -> Option<NetworkSpecs> {
+    default_network_specs()
+        .into_iter()
+        .find(|specs| specs.genesis_hash == *hash)
+}
+
+/// Find default network specs by network `name`, case-insensitive
+pub fn network_specs_by_name(name: &str) -> Option<NetworkSpecs> {
+    default_network_specs()
+        .into_iter()
+        .find(|specs| specs.name.eq_ignore_ascii_case(name))
+}
+
+/// Get embedded SVG logo of a default network, by `logo` field of the
+/// network specs
+///
+/// Only Kusama, Polkadot, and Westend logos are embedded, other networks,
+/// including other default ones, produce `None`.
+#[cfg(feature = "assets")]
+pub fn logo_svg(logo: &str) -> Option<&'static [u8]> {
+    match logo {
+        "kusama" => Some(&include_bytes!("../assets/logos/kusama.svg")[..]),
+        "polkadot" => Some(&include_bytes!("../assets/logos/polkadot.svg")[..]),
+        "westend" => Some(&include_bytes!("../assets/logos/westend.svg")[..]),
+        _ => None,
+    }
+}
+
+/// Search `existing` network specs for networks with the same `base58prefix`
+/// as `new` network specs, but with different genesis hash.
+///
+/// Addresses of such networks have identical representation, so custom
+/// network addition should warn the user about the conflict.
+///
+/// Some prefixes (e.g. generic substrate prefix `42`) are intentionally
+/// shared by many networks, so it is up to the caller to decide whether the
+/// conflict is acceptable.
+///
+/// Existing network specs with all-zero genesis hash have no valid
+/// [`NetworkSpecsKey`], and are skipped.
+pub fn prefix_conflicts(new: &NetworkSpecs, existing: &[NetworkSpecs]) -> Vec<NetworkSpecsKey> {
+    existing
+        .iter()
+        .filter(|specs| {
+            specs.base58prefix == new.base58prefix && specs.genesis_hash != new.genesis_hash
+        })
+        .filter_map(|specs| {
+            NetworkSpecsKey::from_parts(&specs.genesis_hash, &specs.encryption).ok()
+        })
+        .collect()
+}
+
+/// Group network specs by the [`Encryption`] the networks use
+///
+/// Used to list networks compatible with a key of given encryption.
+/// Groups are ordered by [`Encryption`], network specs within the group keep
+/// the input order.
+pub fn group_by_encryption(specs: &[NetworkSpecs]) -> BTreeMap<Encryption, Vec<&NetworkSpecs>> {
+    let mut groups: BTreeMap<Encryption, Vec<&NetworkSpecs>> = BTreeMap::new();
+    for network_specs in specs {
+        groups
+            .entry(network_specs.encryption)
+            .or_default()
+            .push(network_specs);
+    }
+    groups
+}
+
+/// Find addresses that removal of the network with given
+/// [`NetworkSpecsKey`] would orphan
+///
+/// Each address is bound to at most one network, through `network_id` field
+/// of [`AddressDetails`], so every address bound to the removed network
+/// would be left without network. Output is the indices of such addresses in
+/// `addresses`, empty when the network could be removed safely. Deciding
+/// whether to refuse the removal, or to warn the user and remove the
+/// addresses as well, is up to the caller. Root addresses are not bound to
+/// any network and are never orphaned.
+///
+/// Errors if `specs_key` could not be decoded, i.e. does not correspond to
+/// any network.
+pub fn remove_network(
+    specs_key: &NetworkSpecsKey,
+    addresses: &[AddressDetails],
+) -> DefinitionResult<Vec<usize>> {
+    specs_key.genesis_hash_encryption()?;
+    Ok(addresses
+        .iter()
+        .enumerate()
+        .filter(|(_, address_details)| address_details.network_id.as_ref() == Some(specs_key))
+        .map(|(i, _)| i)
+        .collect())
+}
+
+/// Check if derivation path is exactly the network default derivation path
+/// `path_id`
+///
+/// Address with such path duplicates the default network address, that the
+/// wallet creates automatically. Only surrounding whitespace is ignored:
+/// junctions are case-sensitive, soft junction `/polkadot` and passworded
+/// `//polkadot///password` produce different keys and are not default paths.
+pub fn is_default_path(specs: &NetworkSpecs, path: &str) -> bool {
+    path.trim() == specs.path_id
+}
+
+/// Check if `existing` network specs have a network with different genesis
+/// hash, but the same `title` as `new` network specs
+///
+/// Networks with the same title are indistinguishable in Vault menus, so
+/// custom network addition should warn the user. It is up to the caller to
+/// decide whether to allow such network.
+pub fn title_conflicts(new: &NetworkSpecs, existing: &[NetworkSpecs]) -> bool {
+    existing
+        .iter()
+        .any(|specs| specs.title == new.title && specs.genesis_hash != new.genesis_hash)
+}
+
+/// Check network specs `base58prefix` against the `SS58Prefix` constant of
+/// the chain itself
+///
+/// Network specs with wrong prefix silently produce wrong addresses. Wallet
+/// stays transport-agnostic: `fetch` is provided by the integrator and gets
+/// the chain constant, e.g. through RPC call to the network `address`.
+pub fn verify_prefix_on_chain<F>(specs: &NetworkSpecs, fetch: F) -> DefinitionResult<()>
+where
+    F: Fn() -> DefinitionResult<u16>,
+{
+    let on_chain = fetch()?;
+    if on_chain != specs.base58prefix {
+        return Err(DefinitionError::PrefixMismatch {
+            name: specs.name.to_string(),
+            specs_prefix: specs.base58prefix,
+            on_chain_prefix: on_chain,
+        });
+    }
+    Ok(())
+}
+
+/// Apply trusted network specs update, keeping the user customizations
+///
+/// Everything that defines the network and its addresses (`base58prefix`,
+/// `decimals`, `encryption`, `name`, `path_id`, `unit`, `address`) is taken
+/// from `incoming`. Cosmetic fields the user could have changed locally
+/// (`title`, `logo`, `color`, `secondary_color`) are kept from `local`.
+///
+/// Update must be for the same network, i.e. genesis hashes must match.
+pub fn merge_network_update(
+    local: &NetworkSpecs,
+    incoming: &NetworkSpecs,
+) -> DefinitionResult<NetworkSpecs> {
+    if local.genesis_hash != incoming.genesis_hash {
+        return Err(DefinitionError::NetworkUpdateGenesisHashMismatch {
+            local: local.genesis_hash,
+            incoming: incoming.genesis_hash,
+        });
+    }
+    Ok(NetworkSpecs {
+        title: local.title.to_string(),
+        logo: local.logo.to_string(),
+        color: local.color.to_string(),
+        secondary_color: local.secondary_color.to_string(),
+        ..incoming.clone()
+    })
+}
+
+/// Find network specs with RPC `address` matching `url`
+///
+/// Addresses are compared normalized: case, `ws://` or `wss://` scheme and
+/// trailing slashes are ignored, so that `wss://rpc.polkadot.io/` matches
+/// `wss://rpc.polkadot.io`.
+pub fn network_for_rpc<'a>(specs: &'a [NetworkSpecs], url: &str) -> Option<&'a NetworkSpecs> {
+    let url = normalize_rpc_url(url);
+    specs
+        .iter()
+        .find(|network_specs| normalize_rpc_url(&network_specs.address) == url)
+}
+
+/// Normalize RPC URL for comparison
+fn normalize_rpc_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let without_scheme = url
+        .strip_prefix("wss://")
+        .or_else(|| url.strip_prefix("ws://"))
+        .unwrap_or(&url);
+    without_scheme.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(network_id: Option<NetworkSpecsKey>) -> AddressDetails {
+        AddressDetails {
+            seed_name: String::from("Alice"),
+            path: String::from("//0"),
+            has_pwd: false,
+            network_id,
+            encryption: Encryption::Sr25519,
+            public_key: vec![0; 32],
+            secret_exposed: false,
+        }
+    }
+
+    fn specs_key(specs: &NetworkSpecs) -> NetworkSpecsKey {
+        NetworkSpecsKey::from_parts(&specs.genesis_hash, &specs.encryption).unwrap()
+    }
+
+    #[test]
+    fn zero_genesis_hash_rejected() {
+        assert!(matches!(
+            NetworkSpecsKey::from_parts(&H256::zero(), &Encryption::Sr25519),
+            Err(DefinitionError::ZeroGenesisHash)
+        ));
+        let polkadot = NetworkSpecs::polkadot();
+        assert!(NetworkSpecsKey::from_parts(&polkadot.genesis_hash, &polkadot.encryption).is_ok());
+        let placeholder = NetworkSpecs {
+            genesis_hash: H256::zero(),
+            ..NetworkSpecs::westend()
+        };
+        assert!(matches!(
+            placeholder.validate(),
+            Err(DefinitionError::ZeroGenesisHash)
+        ));
+    }
+
+    #[test]
+    fn prefix_conflicts_found() {
+        let custom = NetworkSpecs {
+            genesis_hash: H256::repeat_byte(1),
+            ..NetworkSpecs::polkadot()
+        };
+        let existing = default_network_specs();
+        assert_eq!(
+            prefix_conflicts(&custom, &existing),
+            [
+                specs_key(&NetworkSpecs::polkadot()),
+                specs_key(&NetworkSpecs::polkadot_asset_hub()),
+                specs_key(&NetworkSpecs::paseo()),
+            ]
+        );
+        assert!(prefix_conflicts(&NetworkSpecs::westend(), &existing).is_empty());
+    }
+
+    #[test]
+    fn remove_network_with_bound_address() {
+        let polkadot = specs_key(&NetworkSpecs::polkadot());
+        let kusama = specs_key(&NetworkSpecs::kusama());
+        let addresses = [
+            address(None),
+            address(Some(polkadot.clone())),
+            address(Some(kusama.clone())),
+            address(Some(polkadot.clone())),
+        ];
+        assert_eq!(remove_network(&polkadot, &addresses).unwrap(), [1, 3]);
+        assert_eq!(remove_network(&kusama, &addresses).unwrap(), [2]);
+        let westend = specs_key(&NetworkSpecs::westend());
+        assert_eq!(
+            remove_network(&westend, &addresses).unwrap(),
+            Vec::<usize>::new()
+        );
+        assert!(remove_network(&NetworkSpecsKey(vec![0; 5]), &addresses).is_err());
+    }
+
+    #[test]
+    fn networks_grouped_by_encryption() {
+        let ethereum = NetworkSpecs {
+            encryption: Encryption::Ethereum,
+            genesis_hash: H256::repeat_byte(1),
+            ..NetworkSpecs::westend()
+        };
+        let ed25519 = NetworkSpecs {
+            encryption: Encryption::Ed25519,
+            ..NetworkSpecs::polkadot()
+        };
+        let specs = [
+            NetworkSpecs::kusama(),
+            ethereum.clone(),
+            NetworkSpecs::polkadot(),
+            ed25519.clone(),
+        ];
+        let groups = group_by_encryption(&specs);
+        assert_eq!(
+            groups.keys().copied().collect::<Vec<_>>(),
+            [
+                Encryption::Ed25519,
+                Encryption::Sr25519,
+                Encryption::Ethereum
+            ]
+        );
+        assert_eq!(groups[&Encryption::Ed25519], [&ed25519]);
+        assert_eq!(
+            groups[&Encryption::Sr25519],
+            [&NetworkSpecs::kusama(), &NetworkSpecs::polkadot()]
+        );
+        assert_eq!(groups[&Encryption::Ethereum], [&ethereum]);
+        assert!(group_by_encryption(&[]).is_empty());
+    }
+
+    #[test]
+    fn default_path_matched_exactly() {
+        let polkadot = NetworkSpecs::polkadot();
+        assert!(is_default_path(&polkadot, "//polkadot"));
+        assert!(is_default_path(&polkadot, " //polkadot\n"));
+        for path in [
+            "/polkadot",
+            "//Polkadot",
+            "//polkadot///password",
+            "//polkadot//0",
+            "//kusama",
+        ] {
+            assert!(!is_default_path(&polkadot, path));
+        }
+    }
+
+    #[test]
+    fn network_specs_key_length_checked() {
+        let polkadot = NetworkSpecs::polkadot();
+        let key = specs_key(&polkadot);
+        assert_eq!(
+            key.genesis_hash_encryption().unwrap(),
+            (polkadot.genesis_hash, Encryption::Sr25519)
+        );
+        let mut longer = key.key();
+        longer.push(0);
+        let shorter = &key.key()[..32];
+        for bytes in [longer, shorter.to_vec(), Vec::new()] {
+            assert!(matches!(
+                NetworkSpecsKey(bytes).genesis_hash_encryption(),
+                Err(DefinitionError::WrongNetworkSpecsKeyLength)
+            ));
+        }
+    }
+
+    #[test]
+    fn title_conflicts_found() {
+        let existing = default_network_specs();
+        let same_title = NetworkSpecs {
+            genesis_hash: H256::repeat_byte(1),
+            ..NetworkSpecs::polkadot()
+        };
+        assert!(title_conflicts(&same_title, &existing));
+        assert!(!title_conflicts(&NetworkSpecs::polkadot(), &existing));
+        let other_title = NetworkSpecs {
+            title: String::from("Polkadot Testnet"),
+            ..same_title
+        };
+        assert!(!title_conflicts(&other_title, &existing));
+    }
+
+    #[test]
+    fn prefix_checked_on_chain() {
+        let kusama = NetworkSpecs::kusama();
+        assert!(verify_prefix_on_chain(&kusama, || Ok(2)).is_ok());
+        assert!(matches!(
+            verify_prefix_on_chain(&kusama, || Ok(42)),
+            Err(DefinitionError::PrefixMismatch {
+                name,
+                specs_prefix: 2,
+                on_chain_prefix: 42,
+            }) if name == "kusama"
+        ));
+        assert!(matches!(
+            verify_prefix_on_chain(&kusama, || Err(DefinitionError::ZeroGenesisHash)),
+            Err(DefinitionError::ZeroGenesisHash)
+        ));
+    }
+
+    #[test]
+    fn network_found_by_rpc() {
+        let specs = default_network_specs();
+        for url in [
+            "wss://rpc.polkadot.io",
+            "wss://rpc.polkadot.io/",
+            "ws://rpc.polkadot.io",
+            " WSS://RPC.Polkadot.io// ",
+            "rpc.polkadot.io",
+        ] {
+            assert_eq!(
+                network_for_rpc(&specs, url).map(|network| network.name.as_str()),
+                Some("polkadot"),
+                "{url}"
+            );
+        }
+        assert!(network_for_rpc(&specs, "wss://rpc.polkadot.io/path").is_none());
+        assert!(network_for_rpc(&specs, "https://rpc.polkadot.io").is_none());
+    }
+
+    #[test]
+    fn network_update_keeps_cosmetics() {
+        let local = NetworkSpecs {
+            title: String::from("My Westend"),
+            logo: String::from("custom"),
+            color: String::from("#123456"),
+            secondary_color: String::from("#654321"),
+            ..NetworkSpecs::westend()
+        };
+        let incoming = NetworkSpecs {
+            address: String::from("wss://westend.example.com"),
+            decimals: 10,
+            unit: String::from("WND2"),
+            ..NetworkSpecs::westend()
+        };
+        let merged = merge_network_update(&local, &incoming).unwrap();
+        assert_eq!(merged.title, "My Westend");
+        assert_eq!(merged.logo, "custom");
+        assert_eq!(merged.color, "#123456");
+        assert_eq!(merged.secondary_color, "#654321");
+        assert_eq!(merged.address, "wss://westend.example.com");
+        assert_eq!(merged.decimals, 10);
+        assert_eq!(merged.unit, "WND2");
+        assert_eq!(merged.genesis_hash, incoming.genesis_hash);
+        let polkadot = NetworkSpecs::polkadot();
+        assert!(matches!(
+            merge_network_update(&local, &polkadot),
+            Err(DefinitionError::NetworkUpdateGenesisHashMismatch { local: l, incoming: i })
+                if l == local.genesis_hash && i == polkadot.genesis_hash
+        ));
+    }
+
+    #[test]
+    fn network_specs_key_from_hex_validated() {
+        let polkadot = NetworkSpecs::polkadot();
+        let key = specs_key(&polkadot);
+        let hex_key = hex::encode(key.key());
+        for hex_entry in [hex_key.to_string(), format!("0x{hex_key}")] {
+            assert_eq!(NetworkSpecsKey::from_hex(&hex_entry).unwrap(), key);
+        }
+        let unknown_variant = format!("07{}", &hex_key[2..]);
+        assert!(NetworkSpecsKey::from_hex(&unknown_variant).is_err());
+        assert!(matches!(
+            NetworkSpecsKey::from_hex(&hex_key[..64]),
+            Err(DefinitionError::WrongNetworkSpecsKeyLength)
+        ));
+        assert!(NetworkSpecsKey::from_hex("not hex").is_err());
+    }
+
+    #[test]
+    fn relay_chain_constructors() {
+        for (specs, genesis_hash, base58prefix, unit) in [
+            (
+                NetworkSpecs::polkadot(),
+                "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3",
+                0,
+                "DOT",
+            ),
+            (
+                NetworkSpecs::kusama(),
+                "b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe",
+                2,
+                "KSM",
+            ),
+            (
+                NetworkSpecs::westend(),
+                "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+                42,
+                "WND",
+            ),
+        ] {
+            assert_eq!(hex::encode(specs.genesis_hash), genesis_hash);
+            assert_eq!(specs.base58prefix, base58prefix);
+            assert_eq!(specs.unit, unit);
+            assert_eq!(specs.encryption, Encryption::Sr25519);
+            assert_eq!(specs.path_id, format!("//{}", specs.name));
+            specs.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn default_networks_looked_up() {
+        let westend = NetworkSpecs::westend();
+        assert_eq!(
+            network_specs_by_genesis(&westend.genesis_hash),
+            Some(westend.clone())
+        );
+        assert_eq!(network_specs_by_genesis(&H256::repeat_byte(1)), None);
+        for name in ["westend", "Westend", "WESTEND"] {
+            assert_eq!(network_specs_by_name(name), Some(westend.clone()));
+        }
+        assert_eq!(
+            network_specs_by_name("statemint"),
+            Some(NetworkSpecs::polkadot_asset_hub())
+        );
+        assert_eq!(network_specs_by_name("rococo"), None);
+    }
+
+    #[test]
+    fn asset_hubs_and_paseo_included() {
+        let specs = default_network_specs();
+        let names: Vec<&str> = specs.iter().map(|network| network.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "kusama",
+                "polkadot",
+                "westend",
+                "statemint",
+                "statemine",
+                "paseo"
+            ]
+        );
+        for (network, genesis_hash, base58prefix, unit) in [
+            (
+                NetworkSpecs::polkadot_asset_hub(),
+                "68d56f15f85d3136970ec16946040bc1752654e906147f7e43e9d539d7c3de2f",
+                0,
+                "DOT",
+            ),
+            (
+                NetworkSpecs::kusama_asset_hub(),
+                "48239ef607d7928874027a43a67689209727dfb3d3dc5e5b03a39bdc2eda771a",
+                2,
+                "KSM",
+            ),
+            (
+                NetworkSpecs::paseo(),
+                "77afd6190f1554ad45fd0d31aee62aacc33c6db0ea801129acb813f913e0764f",
+                0,
+                "PAS",
+            ),
+        ] {
+            assert_eq!(hex::encode(network.genesis_hash), genesis_hash);
+            assert_eq!(network.base58prefix, base58prefix);
+            assert_eq!(network.unit, unit);
+            network.validate().unwrap();
+        }
+        let genesis_hashes: std::collections::BTreeSet<H256> =
+            specs.iter().map(|network| network.genesis_hash).collect();
+        assert_eq!(genesis_hashes.len(), specs.len());
+    }
+
+    #[test]
+    fn network_specs_validated() {
+        NetworkSpecs::westend().validate().unwrap();
+        NetworkSpecs {
+            base58prefix: MAX_BASE58_PREFIX,
+            ..NetworkSpecs::westend()
+        }
+        .validate()
+        .unwrap();
+        for base58prefix in [46, 47, MAX_BASE58_PREFIX + 1, u16::MAX] {
+            let specs = NetworkSpecs {
+                base58prefix,
+                ..NetworkSpecs::westend()
+            };
+            assert!(matches!(
+                specs.validate(),
+                Err(DefinitionError::InvalidBase58Prefix(p)) if p == base58prefix
+            ));
+        }
+        let unnamed = NetworkSpecs {
+            name: String::new(),
+            ..NetworkSpecs::westend()
+        };
+        assert!(matches!(
+            unnamed.validate(),
+            Err(DefinitionError::EmptyNetworkName)
+        ));
+        let no_unit = NetworkSpecs {
+            unit: String::new(),
+            ..NetworkSpecs::westend()
+        };
+        assert!(matches!(
+            no_unit.validate(),
+            Err(DefinitionError::EmptyNetworkUnit)
+        ));
+    }
+
+    #[cfg(feature = "assets")]
+    #[test]
+    fn default_logos_embedded() {
+        for specs in [
+            NetworkSpecs::kusama(),
+            NetworkSpecs::polkadot(),
+            NetworkSpecs::westend(),
+        ] {
+            let svg = logo_svg(&specs.logo).unwrap();
+            assert!(svg.starts_with(b"<svg"), "{}", specs.logo);
+        }
+        for logo in ["statemint", "paseo", "custom", ""] {
+            assert!(logo_svg(logo).is_none(), "{logo}");
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Signing payload inspection
+//!
+//! Vault receives signable transaction as SCALE-encoded call, prefixed with
+//! its compact length, followed by the signed extensions. Signed extensions
+//! differ between networks, and are listed in network metadata. For default
+//! networks, the signed extensions are, in order:
+//!
+//! - `Era`, transaction mortality
+//! - `Compact<u32>`, transaction nonce
+//! - `Compact<u128>`, tip for the block author
+//! - `Option<AssetId>`, asset the fee is paid in, only on Asset Hub networks
+//!   with `ChargeAssetTxPayment`
+//! - `u8`, metadata hash check mode, only with `CheckMetadataHash`
+//! - `u32`, runtime specification version
+//! - `u32`, transaction version
+//! - `H256`, network genesis hash
+//! - `H256`, block hash, equal to genesis hash for immortal transactions
+//! - `Option<H256>`, metadata hash, only with `CheckMetadataHash`
+//!
+//! Call decoding requires network metadata, signed extensions need only the
+//! [`ExtensionsLayout`], so the signed extensions could be inspected and
+//! displayed before the call is decoded.
+use codec::{Compact, Decode};
+use sp_core::H256;
+use sp_runtime::generic::Era;
+
+use crate::{
+    clock::Clock,
+    error::{DefinitionError, DefinitionResult},
+    network_spec::NetworkSpecs,
+};
+
+/// Signed extensions present in the network, that change the signing
+/// payload layout
+///
+/// Default value is the layout without optional extensions, as it was on
+/// all default networks before `CheckMetadataHash` was introduced.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtensionsLayout {
+    /// `ChargeAssetTxPayment` is used instead of `ChargeTransactionPayment`,
+    /// tip is followed by optional asset id
+    pub asset_tx_payment: bool,
+
+    /// `CheckMetadataHash` is used, tip is followed by mode byte, and block
+    /// hash is followed by optional metadata hash
+    pub metadata_hash: bool,
+}
+
+/// Signed extensions that are in the signing payload, in the order they are
+/// expected in network metadata
+const PAYLOAD_EXTENSIONS: [&str; 7] = [
+    "CheckSpecVersion",
+    "CheckTxVersion",
+    "CheckGenesis",
+    "CheckMortality",
+    "CheckNonce",
+    "ChargeTransactionPayment",
+    "CheckMetadataHash",
+];
+
+impl ExtensionsLayout {
+    /// Get [`ExtensionsLayout`] from signed extension identifiers, in the
+    /// order they are listed in network metadata
+    ///
+    /// Extensions adding nothing to the payload, e.g. `CheckNonZeroSender`
+    /// and `CheckWeight`, are skipped. Unknown extensions could add anything
+    /// to the payload and result in error, same as known extensions in
+    /// unexpected order.
+    pub fn from_identifiers<'a, I>(identifiers: I) -> DefinitionResult<Self>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut layout = Self::default();
+        let mut payload_extensions = Vec::new();
+        for identifier in identifiers {
+            match identifier {
+                "CheckNonZeroSender"
+                | "CheckWeight"
+                | "PrevalidateAttests"
+                | "StorageWeightReclaim"
+                | "WeightReclaim"
+                | "AuthorizeCall" => {}
+                "CheckSpecVersion"
+                | "CheckTxVersion"
+                | "CheckGenesis"
+                | "CheckNonce"
+                | "ChargeTransactionPayment" => payload_extensions.push(identifier),
+                "CheckMortality" | "CheckEra" => payload_extensions.push("CheckMortality"),
+                "ChargeAssetTxPayment" => {
+                    layout.asset_tx_payment = true;
+                    payload_extensions.push("ChargeTransactionPayment");
+                }
+                "CheckMetadataHash" => {
+                    layout.metadata_hash = true;
+                    payload_extensions.push(identifier);
+                }
+                _ => {
+                    return Err(DefinitionError::UnknownSignedExtension(
+                        identifier.to_string(),
+                    ))
+                }
+            }
+        }
+        let expected_len = PAYLOAD_EXTENSIONS.len() - usize::from(!layout.metadata_hash);
+        if payload_extensions[..] != PAYLOAD_EXTENSIONS[..expected_len] {
+            return Err(DefinitionError::UnsupportedSignedExtensionsOrder);
+        }
+        Ok(layout)
+    }
+}
+
+/// Get transaction [`Era`](https://docs.rs/sp-runtime/6.0.0/sp_runtime/generic/enum.Era.html)
+/// from the signing payload
+///
+/// Immortal transaction could be replayed at any time, so it is risky to
+/// sign blindly, and user should be warned.
+pub fn inspect_era(payload: &[u8]) -> DefinitionResult<Era> {
+    Ok(Era::decode(&mut extensions(payload)?)?)
+}
+
+/// Block known to the host, reference point to estimate the current block
+/// from the wall clock
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlockCheckpoint {
+    /// block number
+    pub number: u64,
+
+    /// block timestamp, seconds since Unix epoch
+    pub timestamp: u64,
+}
+
+/// Make mortal [`Era`] for a transaction valid for about `period` blocks
+/// starting now
+///
+/// Current block is estimated from the `checkpoint`, assuming `block_time`
+/// seconds per block. Clock behind the checkpoint is treated as the
+/// checkpoint time, so that the era never starts before the known block.
+pub fn mortal_era<C: Clock>(
+    period: u64,
+    checkpoint: &BlockCheckpoint,
+    block_time: u64,
+    clock: &C,
+) -> Era {
+    let elapsed = clock.now_unix().saturating_sub(checkpoint.timestamp);
+    let current = checkpoint
+        .number
+        .saturating_add(elapsed / block_time.max(1));
+    Era::mortal(period, current)
+}
+
+/// Signed extensions content, displayed for user approval before signing
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SignedExtra {
+    /// transaction mortality
+    pub era: Era,
+
+    /// transaction nonce
+    pub nonce: u32,
+
+    /// tip for the block author, in balance integer units
+    pub tip: u128,
+
+    /// SCALE-encoded asset id the fee is paid in, `None` for native token
+    pub asset_id: Option<Vec<u8>>,
+
+    /// metadata hash the transaction is checked against, `None` if the
+    /// check is disabled
+    pub metadata_hash: Option<H256>,
+
+    /// runtime specification version the transaction is built for
+    pub spec_version: u32,
+
+    /// transaction version the transaction is built for
+    pub transaction_version: u32,
+}
+
+/// Get [`SignedExtra`] from the signing payload
+///
+/// Nonce and tip are compact-encoded. Signed extensions must match the
+/// `layout` and must end the payload, truncated or trailing data results in
+/// error.
+///
+/// `layout` is needed, unlike for [`inspect_era`]: asset id and metadata
+/// hash mode sit between the tip and the versions, and could not be told
+/// apart from other data without knowing which extensions the network has.
+/// Take it from the network metadata with
+/// [`ExtensionsLayout::from_identifiers`].
+pub fn inspect_signed_extra(
+    payload: &[u8],
+    layout: &ExtensionsLayout,
+) -> DefinitionResult<SignedExtra> {
+    Ok(decode_signed_extensions(extensions(payload)?, layout)?.0)
+}
+
+/// Get network genesis hash from the signing payload
+///
+/// Genesis hash follows the runtime and transaction versions in the
+/// additional signed data at the end of signed extensions.
+///
+/// Its offset from the payload end depends on whether the network has
+/// `CheckMetadataHash`, so the `layout` is needed. Guessing the layout here
+/// would let a crafted payload place a chosen hash where the genesis hash
+/// is expected for the other layout.
+pub fn payload_genesis_hash(payload: &[u8], layout: &ExtensionsLayout) -> DefinitionResult<H256> {
+    Ok(decode_signed_extensions(extensions(payload)?, layout)?.1)
+}
+
+/// Check that the signing payload is for the network with given
+/// [`NetworkSpecs`]
+///
+/// Must be done before signing: otherwise user could be tricked into signing
+/// transaction for a different network than the one of selected address.
+///
+/// [`NetworkSpecs`] carry no signed extensions, so `layout` of the same
+/// network is passed separately, see [`payload_genesis_hash`].
+pub fn assert_payload_matches_network(
+    payload: &[u8],
+    network_specs: &NetworkSpecs,
+    layout: &ExtensionsLayout,
+) -> DefinitionResult<()> {
+    let payload_genesis_hash = payload_genesis_hash(payload, layout)?;
+    if payload_genesis_hash != network_specs.genesis_hash {
+        return Err(DefinitionError::PayloadGenesisHashMismatch {
+            expected: network_specs.genesis_hash,
+            found: payload_genesis_hash,
+        });
+    }
+    Ok(())
+}
+
+/// Length of runtime and transaction versions, genesis hash and block hash
+/// in additional signed data
+const ADDITIONAL_SIGNED_LEN: usize = 4 + 4 + 32 + 32;
+
+/// Decode [`SignedExtra`] and genesis hash from the signed extensions
+///
+/// Era, nonce and tip are decoded from the start. Additional signed data is
+/// fixed-size for given metadata hash presence, and is decoded from the end.
+/// Asset id, if any, is everything in between, and is kept encoded, as its
+/// type is network-specific.
+///
+/// Metadata hash is `None` exactly when the mode byte disables the check, so
+/// both possible metadata hash lengths are tried, and exactly one must be
+/// consistent with the mode byte and leave no extra data.
+fn decode_signed_extensions(
+    mut data: &[u8],
+    layout: &ExtensionsLayout,
+) -> DefinitionResult<(SignedExtra, H256)> {
+    let era = Era::decode(&mut data)?;
+    let nonce = <Compact<u32>>::decode(&mut data)?.0;
+    let tip = <Compact<u128>>::decode(&mut data)?.0;
+    let metadata_hash_lengths: &[usize] = if layout.metadata_hash { &[1, 33] } else { &[0] };
+    let mut candidates = metadata_hash_lengths
+        .iter()
+        .filter_map(|metadata_hash_len| split_tail(data, layout, *metadata_hash_len));
+    let (asset_id, metadata_hash, mut additional_signed) =
+        match (candidates.next(), candidates.next()) {
+            (Some(candidate), None) => candidate,
+            _ => return Err(DefinitionError::PayloadExtensionsMismatch),
+        };
+    let spec_version = u32::decode(&mut additional_signed)?;
+    let transaction_version = u32::decode(&mut additional_signed)?;
+    let genesis_hash = H256::decode(&mut additional_signed)?;
+    Ok((
+        SignedExtra {
+            era,
+            nonce,
+            tip,
+            asset_id,
+            metadata_hash,
+            spec_version,
+            transaction_version,
+        },
+        genesis_hash,
+    ))
+}
+
+/// Asset id, metadata hash and additional signed data, as split by
+/// [`split_tail`]
+type SplitTail<'a> = (Option<Vec<u8>>, Option<H256>, &'a [u8]);
+
+/// Split signed extensions remaining after the tip, assuming metadata hash
+/// of `metadata_hash_len` bytes at the very end
+///
+/// `None` if the remaining data does not fit the `layout` this way.
+fn split_tail<'a>(
+    data: &'a [u8],
+    layout: &ExtensionsLayout,
+    metadata_hash_len: usize,
+) -> Option<SplitTail<'a>> {
+    let mode_len = usize::from(layout.metadata_hash);
+    let tail_start = data
+        .len()
+        .checked_sub(mode_len + ADDITIONAL_SIGNED_LEN + metadata_hash_len)?;
+    let (asset_id, tail) = data.split_at(tail_start);
+    let (mode, tail) = tail.split_at(mode_len);
+    let (additional_signed, mut metadata_hash) = tail.split_at(ADDITIONAL_SIGNED_LEN);
+    let metadata_hash = if layout.metadata_hash {
+        let decoded = <Option<H256>>::decode(&mut metadata_hash).ok()?;
+        match (mode, decoded) {
+            ([0], None) | ([1], Some(_)) => decoded,
+            _ => return None,
+        }
+    } else {
+        None
+    };
+    let asset_id = match (layout.asset_tx_payment, asset_id) {
+        (false, []) | (true, [0]) => None,
+        (true, [1, asset_id @ ..]) if !asset_id.is_empty() => Some(asset_id.to_vec()),
+        _ => return None,
+    };
+    Some((asset_id, metadata_hash, additional_signed))
+}
+
+/// Cut the length-prefixed call from the signing payload, get the signed
+/// extensions part
+fn extensions(payload: &[u8]) -> DefinitionResult<&[u8]> {
+    let mut data = payload;
+    let call_length = <Compact<u32>>::decode(&mut data)?.0 as usize;
+    data.get(call_length..)
+        .ok_or(DefinitionError::PayloadTooShort)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use crate::clock::FixedClock;
+
+    /// Signed extension identifiers of current Westend runtime
+    const WESTEND_EXTENSIONS: [&str; 10] = [
+        "CheckNonZeroSender",
+        "CheckSpecVersion",
+        "CheckTxVersion",
+        "CheckGenesis",
+        "CheckMortality",
+        "CheckNonce",
+        "CheckWeight",
+        "ChargeTransactionPayment",
+        "CheckMetadataHash",
+        "WeightReclaim",
+    ];
+
+    /// Westend `system.remark("hi")` signing payload: era mortal for 64
+    /// blocks, nonce 5, tip 0.1 WND, metadata hash check disabled, runtime
+    /// 1016001, transaction version 26
+    ///
+    /// Hand-built from the layout in the module doc, not captured from a
+    /// wallet: genesis hash is the real Westend one, block hash `5d6b4a2f…`
+    /// is made up.
+    const WESTEND_PAYLOAD: &str = concat!(
+        "14",
+        "0000086869",
+        "d501",
+        "14",
+        "0700e8764817",
+        "00",
+        "c1800f00",
+        "1a000000",
+        "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+        "5d6b4a2f8e3c1b9a7d0e2f4c6a8b1d3e5f7092a4c6e8b0d2f4a6c8e0b2d4f6a8",
+        "00",
+    );
+
+    fn westend_layout() -> ExtensionsLayout {
+        ExtensionsLayout::from_identifiers(WESTEND_EXTENSIONS).unwrap()
+    }
+
+    #[test]
+    fn layout_from_westend_metadata() {
+        assert_eq!(
+            westend_layout(),
+            ExtensionsLayout {
+                asset_tx_payment: false,
+                metadata_hash: true,
+            }
+        );
+    }
+
+    #[test]
+    fn layout_rejects_unknown_and_misordered_extensions() {
+        let mut extensions = WESTEND_EXTENSIONS.to_vec();
+        extensions.push("CheckSomethingNew");
+        assert!(matches!(
+            ExtensionsLayout::from_identifiers(extensions),
+            Err(DefinitionError::UnknownSignedExtension(identifier)) if identifier == "CheckSomethingNew"
+        ));
+        let mut extensions = WESTEND_EXTENSIONS.to_vec();
+        extensions.swap(4, 5);
+        assert!(matches!(
+            ExtensionsLayout::from_identifiers(extensions),
+            Err(DefinitionError::UnsupportedSignedExtensionsOrder)
+        ));
+    }
+
+    #[test]
+    fn decodes_westend_payload() {
+        let payload = hex::decode(WESTEND_PAYLOAD).unwrap();
+        let layout = westend_layout();
+        assert_eq!(inspect_era(&payload).unwrap(), Era::Mortal(64, 29));
+        assert_eq!(
+            inspect_signed_extra(&payload, &layout).unwrap(),
+            SignedExtra {
+                era: Era::Mortal(64, 29),
+                nonce: 5,
+                tip: 100_000_000_000,
+                asset_id: None,
+                metadata_hash: None,
+                spec_version: 1_016_001,
+                transaction_version: 26,
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_enabled_metadata_hash() {
+        let hash = "ab".repeat(32);
+        let payload = WESTEND_PAYLOAD.replacen("0700e876481700", "0700e876481701", 1);
+        let payload = hex::decode(format!("{}01{hash}", &payload[..payload.len() - 2])).unwrap();
+        let extra = inspect_signed_extra(&payload, &westend_layout()).unwrap();
+        assert_eq!(extra.metadata_hash, Some(H256::from_str(&hash).unwrap()));
+        assert_eq!(extra.spec_version, 1_016_001);
+    }
+
+    #[test]
+    fn decodes_asset_id() {
+        let layout = ExtensionsLayout {
+            asset_tx_payment: true,
+            metadata_hash: true,
+        };
+        // asset id `Some` of SCALE-encoded `u32` 1984
+        let payload = WESTEND_PAYLOAD.replacen("0700e876481700", "0700e876481701c007000000", 1);
+        let extra = inspect_signed_extra(&hex::decode(payload).unwrap(), &layout).unwrap();
+        assert_eq!(extra.asset_id, Some(vec![0xc0, 0x07, 0x00, 0x00]));
+        assert_eq!(extra.transaction_version, 26);
+    }
+
+    #[test]
+    fn westend_payload_genesis_hash() {
+        let payload = hex::decode(WESTEND_PAYLOAD).unwrap();
+        let layout = westend_layout();
+        assert_eq!(
+            payload_genesis_hash(&payload, &layout).unwrap(),
+            NetworkSpecs::westend().genesis_hash
+        );
+        assert!(
+            assert_payload_matches_network(&payload, &NetworkSpecs::westend(), &layout).is_ok()
+        );
+        assert!(matches!(
+            assert_payload_matches_network(&payload, &NetworkSpecs::polkadot(), &layout),
+            Err(DefinitionError::PayloadGenesisHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_layout_mismatch() {
+        let payload = hex::decode(WESTEND_PAYLOAD).unwrap();
+        assert!(matches!(
+            inspect_signed_extra(&payload, &ExtensionsLayout::default()),
+            Err(DefinitionError::PayloadExtensionsMismatch)
+        ));
+        let trailing = [&payload[..], &[0]].concat();
+        assert!(matches!(
+            payload_genesis_hash(&trailing, &westend_layout()),
+            Err(DefinitionError::PayloadExtensionsMismatch)
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_payload() {
+        assert!(matches!(
+            inspect_era(&hex::decode("140000").unwrap()),
+            Err(DefinitionError::PayloadTooShort)
+        ));
+        let payload = hex::decode(WESTEND_PAYLOAD).unwrap();
+        assert!(inspect_signed_extra(&payload[..payload.len() - 1], &westend_layout()).is_err());
+    }
+
+    #[test]
+    fn immortal_era_inspected() {
+        let payload = WESTEND_PAYLOAD.replacen("d501", "00", 1);
+        let payload = hex::decode(payload).unwrap();
+        assert_eq!(inspect_era(&payload).unwrap(), Era::Immortal);
+        assert_eq!(
+            inspect_signed_extra(&payload, &westend_layout())
+                .unwrap()
+                .era,
+            Era::Immortal
+        );
+    }
+
+    #[test]
+    fn mortal_era_from_fixed_clock() {
+        let checkpoint = BlockCheckpoint {
+            number: 1000,
+            timestamp: 1_700_000_000,
+        };
+        // 10 blocks of 6 seconds after the checkpoint
+        let era = mortal_era(64, &checkpoint, 6, &FixedClock(1_700_000_060));
+        assert_eq!(era, Era::Mortal(64, 50));
+        assert_eq!(era.birth(1010), 1010);
+        assert_eq!(era.death(1010), 1074);
+        // clock behind the checkpoint
+        assert_eq!(
+            mortal_era(64, &checkpoint, 6, &FixedClock(1_600_000_000)),
+            Era::mortal(64, 1000)
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Network specs import from Vault `add_specs` QR codes
+//!
+//! Vault ecosystem distributes network specs in QR codes, produced by
+//! Vault hot-side tools. Reassembled QR payload layout is:
+//!
+//! - legacy multiframe header: `00`, then big-endian `u16` number of frames
+//!   and `u16` frame index; only single-frame payloads are complete as is,
+//!   and the header could be missing altogether
+//! - prelude `53xxc1`, where `53` is Substrate, `xx` is verifier encryption
+//!   (`00` for `Ed25519`, `01` for `Sr25519`, `02` for `Ecdsa`, `03` for
+//!   `Ethereum`, `ff` for unsigned), and `c1` is `add_specs` payload type
+//! - verifier public key, only if signed
+//! - SCALE-encoded `Vec<u8>` with SCALE-encoded network specs
+//! - verifier signature of the SCALE-encoded `Vec<u8>` above, compact
+//!   length prefix included, only if signed, with nothing after it
+//!
+//! Network specs in the payload are encoded in Vault field order, with
+//! fields sorted alphabetically and without the RPC `address`, so the
+//! [`NetworkSpecs`] itself could not be decoded from it directly.
+//!
+//! Fountain (`raptorq`) multiframe payloads must be reassembled elsewhere.
+use codec::Decode;
+use sp_core::H256;
+use sp_runtime::MultiSignature;
+
+use crate::{
+    crypto::{Encryption, TaggedSignature},
+    error::{DefinitionError, DefinitionResult},
+    helpers::{get_multisigner, verify_signature},
+    network_spec::NetworkSpecs,
+};
+
+/// Substrate payload marker, first byte of the prelude
+const SUBSTRATE_PREFIX: u8 = 0x53;
+
+/// Payload type for network specs update
+const ADD_SPECS: u8 = 0xc1;
+
+/// Prelude encryption byte for unsigned payloads
+const UNSIGNED: u8 = 0xff;
+
+/// Legacy multiframe header length: marker, frames count, frame index
+const MULTIFRAME_HEADER_LEN: usize = 5;
+
+/// Network specs, as encoded in Vault `add_specs` payload
+#[derive(Decode)]
+struct VaultNetworkSpecs {
+    base58prefix: u16,
+    color: String,
+    decimals: u8,
+    encryption: Encryption,
+    genesis_hash: H256,
+    logo: String,
+    name: String,
+    path_id: String,
+    secondary_color: String,
+    title: String,
+    unit: String,
+}
+
+impl From<VaultNetworkSpecs> for NetworkSpecs {
+    fn from(specs: VaultNetworkSpecs) -> Self {
+        NetworkSpecs {
+            base58prefix: specs.base58prefix,
+            decimals: specs.decimals,
+            encryption: specs.encryption,
+            genesis_hash: specs.genesis_hash,
+            logo: specs.logo,
+            name: specs.name,
+            path_id: specs.path_id,
+            secondary_color: specs.secondary_color,
+            title: specs.title,
+            unit: specs.unit,
+            color: specs.color,
+            address: String::new(),
+        }
+    }
+}
+
+/// Get [`NetworkSpecs`] from reassembled Vault `add_specs` QR payload
+///
+/// If the payload is signed, the signature is checked, and invalid
+/// signature results in error. Unsigned payloads are accepted, it is up to
+/// the caller to warn the user. Decoded specs are checked with
+/// [`NetworkSpecs::validate`].
+///
+/// Specs in QR code carry no RPC `address`, it is left empty.
+pub fn decode_network_specs_qr(payload: &[u8]) -> DefinitionResult<NetworkSpecs> {
+    let mut data = strip_multiframe_header(payload)?;
+    let prelude = data.get(..3).ok_or(DefinitionError::QrPayloadTooShort)?;
+    let (substrate, encryption_byte, payload_type) = (prelude[0], prelude[1], prelude[2]);
+    data = &data[3..];
+    if substrate != SUBSTRATE_PREFIX {
+        return Err(DefinitionError::NotSubstrateQr(substrate));
+    }
+    if payload_type != ADD_SPECS {
+        return Err(DefinitionError::NotAddSpecsQr(payload_type));
+    }
+    let verifier = match encryption_byte {
+        UNSIGNED => None,
+        byte => {
+            let encryption = qr_encryption(byte)?;
+            let public_len = match encryption {
+                Encryption::Ed25519 | Encryption::Sr25519 => 32,
+                Encryption::Ecdsa | Encryption::Ethereum => 33,
+            };
+            let public = data
+                .get(..public_len)
+                .ok_or(DefinitionError::QrPayloadTooShort)?;
+            data = &data[public_len..];
+            Some((get_multisigner(public, &encryption)?, encryption))
+        }
+    };
+    let specs_start = data;
+    let specs_encoded = <Vec<u8>>::decode(&mut data)?;
+    if let Some((verifier, encryption)) = verifier {
+        let signed = &specs_start[..specs_start.len() - data.len()];
+        let signature = MultiSignature::try_from(TaggedSignature::new(encryption, data.to_vec()))?;
+        if !verify_signature(&verifier, signed, &signature) {
+            return Err(DefinitionError::QrSignatureInvalid);
+        }
+    }
+    let specs: NetworkSpecs = VaultNetworkSpecs::decode(&mut &specs_encoded[..])?.into();
+    specs.validate()?;
+    Ok(specs)
+}
+
+/// Cut the legacy multiframe header, if any
+///
+/// Single-frame payload is complete after the header is cut, payloads
+/// split into several frames, legacy or fountain, are not.
+fn strip_multiframe_header(payload: &[u8]) -> DefinitionResult<&[u8]> {
+    match payload.first() {
+        Some(0x00) => {
+            let header = payload
+                .get(..MULTIFRAME_HEADER_LEN)
+                .ok_or(DefinitionError::QrPayloadTooShort)?;
+            let frames_count = u16::from_be_bytes([header[1], header[2]]);
+            if frames_count != 1 {
+                return Err(DefinitionError::QrMultiframe);
+            }
+            Ok(&payload[MULTIFRAME_HEADER_LEN..])
+        }
+        Some(byte) if byte & 0x80 != 0 => Err(DefinitionError::QrMultiframe),
+        _ => Ok(payload),
+    }
+}
+
+/// [`Encryption`] for the prelude encryption byte of a signed payload
+fn qr_encryption(byte: u8) -> DefinitionResult<Encryption> {
+    match byte {
+        0x00 => Ok(Encryption::Ed25519),
+        0x01 => Ok(Encryption::Sr25519),
+        0x02 => Ok(Encryption::Ecdsa),
+        0x03 => Ok(Encryption::Ethereum),
+        _ => Err(DefinitionError::UnknownQrEncryption(byte)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use codec::Encode;
+    use sp_core::{sr25519, Pair};
+
+    /// Westend specs, SCALE-encoded in Vault field order
+    fn westend_specs_encoded() -> Vec<u8> {
+        let westend = NetworkSpecs::westend();
+        (
+            westend.base58prefix,
+            westend.color,
+            westend.decimals,
+            westend.encryption,
+            westend.genesis_hash,
+            westend.logo,
+            westend.name,
+            westend.path_id,
+            westend.secondary_color,
+            westend.title,
+            westend.unit,
+        )
+            .encode()
+    }
+
+    /// Westend specs `add_specs` payload signed by `//Alice` `Ed25519` key
+    ///
+    /// Hand-built: assembled and signed with Python `cryptography` following
+    /// the layout in the module doc, not exported from Vault tooling. No
+    /// genuine Vault export was available; it should replace this fixture
+    /// once captured.
+    const WESTEND_ED25519_SIGNED: &str = concat!(
+        "5300c1",
+        "88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee",
+        "6901",
+        "2a001c233636304433350c01",
+        "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+        "1c77657374656e641c77657374656e64242f2f77657374656e64",
+        "1c233236323632361c57657374656e640c574e44",
+        "1b882a33a5b840b287099092a6a6a2bfea59481dd12dcc840190b1dc1ec0d42d",
+        "c099663c5cc2852a5f26708e2a831b4b8123624cb830c50c0000ee6bc35a6103",
+    );
+
+    fn unsigned_payload() -> Vec<u8> {
+        [
+            &[SUBSTRATE_PREFIX, UNSIGNED, ADD_SPECS][..],
+            &westend_specs_encoded().encode(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn unsigned_specs_decoded() {
+        let expected = NetworkSpecs {
+            address: String::new(),
+            ..NetworkSpecs::westend()
+        };
+        let payload = unsigned_payload();
+        assert_eq!(decode_network_specs_qr(&payload).unwrap(), expected);
+        let single_frame = [&[0x00, 0x00, 0x01, 0x00, 0x00][..], &payload].concat();
+        assert_eq!(decode_network_specs_qr(&single_frame).unwrap(), expected);
+    }
+
+    #[test]
+    fn signed_specs_verified() {
+        let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+        let specs_encoded = westend_specs_encoded();
+        let signature = pair.sign(&specs_encoded.encode());
+        let signed = |signature: &[u8]| {
+            [
+                &[SUBSTRATE_PREFIX, 0x01, ADD_SPECS][..],
+                &pair.public().0,
+                &specs_encoded.encode(),
+                signature,
+            ]
+            .concat()
+        };
+        assert_eq!(
+            decode_network_specs_qr(&signed(&signature.0)).unwrap().name,
+            "westend"
+        );
+        // signature of the specs without the length prefix
+        let unprefixed = pair.sign(&specs_encoded);
+        assert!(matches!(
+            decode_network_specs_qr(&signed(&unprefixed.0)),
+            Err(DefinitionError::QrSignatureInvalid)
+        ));
+        let mut tampered = signature.0;
+        tampered[0] ^= 1;
+        assert!(matches!(
+            decode_network_specs_qr(&signed(&tampered)),
+            Err(DefinitionError::QrSignatureInvalid)
+        ));
+        assert!(matches!(
+            decode_network_specs_qr(&signed(&signature.0[..63])),
+            Err(DefinitionError::WrongSignatureLength)
+        ));
+    }
+
+    #[test]
+    fn unsupported_payloads_rejected() {
+        let payload = unsigned_payload();
+        let multiframe = [&[0x00, 0x00, 0x02, 0x00, 0x00][..], &payload].concat();
+        assert!(matches!(
+            decode_network_specs_qr(&multiframe),
+            Err(DefinitionError::QrMultiframe)
+        ));
+        let fountain = [&[0x80][..], &payload].concat();
+        assert!(matches!(
+            decode_network_specs_qr(&fountain),
+            Err(DefinitionError::QrMultiframe)
+        ));
+        let mut load_metadata = payload.clone();
+        load_metadata[2] = 0x80;
+        assert!(matches!(
+            decode_network_specs_qr(&load_metadata),
+            Err(DefinitionError::NotAddSpecsQr(0x80))
+        ));
+        let mut not_substrate = payload.clone();
+        not_substrate[0] = 0x45;
+        assert!(matches!(
+            decode_network_specs_qr(&not_substrate),
+            Err(DefinitionError::NotSubstrateQr(0x45))
+        ));
+        let mut unknown_encryption = payload;
+        unknown_encryption[1] = 0x04;
+        assert!(matches!(
+            decode_network_specs_qr(&unknown_encryption),
+            Err(DefinitionError::UnknownQrEncryption(0x04))
+        ));
+        assert!(matches!(
+            decode_network_specs_qr(&[SUBSTRATE_PREFIX]),
+            Err(DefinitionError::QrPayloadTooShort)
+        ));
+    }
+
+    #[test]
+    fn ed25519_signed_fixture_decoded() {
+        let payload = hex::decode(WESTEND_ED25519_SIGNED).unwrap();
+        assert_eq!(
+            decode_network_specs_qr(&payload).unwrap(),
+            NetworkSpecs {
+                address: String::new(),
+                ..NetworkSpecs::westend()
+            }
+        );
+        // specs part is the same as in the unsigned payload
+        assert_eq!(&payload[35..payload.len() - 64], &unsigned_payload()[3..]);
+        let mut tampered = payload;
+        tampered[40] ^= 1;
+        assert!(matches!(
+            decode_network_specs_qr(&tampered),
+            Err(DefinitionError::QrSignatureInvalid)
+        ));
+    }
+}
@@ -8,15 +8,28 @@
 //!
 //! Release and test versions of the cold database are generated on the Active side.
 
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
+use sp_runtime::MultiSigner;
 
-use crate::{definitions::Encryption, network_spec::NetworkSpecsKey};
+use crate::{
+    crypto::Encryption,
+    error::{DefinitionResult, IdentityError, IdentityResult},
+    helpers::{get_multisigner, print_multisigner_as_base58_or_eth},
+    keyring::AddressKey,
+    network_spec::{NetworkSpecs, NetworkSpecsKey},
+};
 
 /// Address key associated non-secret information stored in Vault database
 ///
 /// Info that should be available for any address key.
 /// No secrets are stored there.
+///
+/// Fields are SCALE-encoded in declaration order. `public_key` was added
+/// last, so values stored before it are the current encoding without the
+/// trailing `public_key`, and are decoded with
+/// [`from_legacy_encoded`](AddressDetails::from_legacy_encoded).
 #[derive(Decode, PartialEq, Eq, Encode, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressDetails {
     /// seed name (as it is known to the Vault device)
     pub seed_name: String,
@@ -36,4 +49,407 @@ pub struct AddressDetails {
 
     /// address, or its parent address, had or could have secret exposed
     pub secret_exposed: bool,
+
+    /// raw public key of the address key, 33 bytes compressed key for `Ecdsa`
+    /// and `Ethereum`, 32 bytes otherwise; saves re-deriving the key for
+    /// display
+    #[cfg_attr(feature = "serde", serde(with = "sp_core::bytes"))]
+    pub public_key: Vec<u8>,
+}
+
+impl AddressDetails {
+    /// Decode [`AddressDetails`] stored before `public_key` field was added
+    ///
+    /// Public key is taken from the `address_key` the value is stored under.
+    /// Value must have no data after `secret_exposed`.
+    pub fn from_legacy_encoded(encoded: &[u8], address_key: &AddressKey) -> IdentityResult<Self> {
+        let (seed_name, path, has_pwd, network_id, encryption, secret_exposed) =
+            <(
+                String,
+                String,
+                bool,
+                Option<NetworkSpecsKey>,
+                Encryption,
+                bool,
+            )>::decode_all(&mut &encoded[..])?;
+        let (public_key, _) = address_key.public_key_encryption()?;
+        Ok(Self {
+            seed_name,
+            path,
+            has_pwd,
+            network_id,
+            encryption,
+            secret_exposed,
+            public_key,
+        })
+    }
+
+    /// Check that `encryption` matches the encryption of the `network_id`
+    ///
+    /// [`NetworkSpecsKey`] encodes its own [`Encryption`], and the two could
+    /// disagree after faulty mutation or import, resulting in wrong address
+    /// display. Should be checked after every mutation or import.
+    pub fn validate(&self) -> IdentityResult<()> {
+        if let Some(network_specs_key) = &self.network_id {
+            let (_, network_encryption) = network_specs_key.genesis_hash_encryption()?;
+            if network_encryption != self.encryption {
+                return Err(IdentityError::InconsistentEncryption {
+                    details_encryption: self.encryption,
+                    network_encryption,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Displayable address for this address key, with given public key
+    ///
+    /// Address is SS58 with `base58prefix` of the network, except for the
+    /// `Ethereum` encryption, where it is hexadecimal Ethereum address and
+    /// `base58prefix` is not used.
+    pub fn address_string(&self, public_key: &[u8], base58prefix: u16) -> DefinitionResult<String> {
+        let multisigner = get_multisigner(public_key, &self.encryption)?;
+        print_multisigner_as_base58_or_eth(&multisigner, Some(base58prefix), self.encryption)
+    }
+
+    /// Make [`WatchOnlyAccount`] for this address, with given public key
+    ///
+    /// Seed name and derivation path are not exported.
+    pub fn to_watch_only(&self, multisigner: &MultiSigner) -> WatchOnlyAccount {
+        WatchOnlyAccount {
+            multisigner: multisigner.to_owned(),
+            encryption: self.encryption,
+            network_id: self.network_id.to_owned(),
+        }
+    }
+
+    /// Check if two [`AddressDetails`] describe the same address key
+    ///
+    /// Only the fields defining the key are compared: seed name, derivation
+    /// path, password presence, network and encryption. Mutable fields, such
+    /// as `secret_exposed`, are ignored, unlike in derived `PartialEq`.
+    pub fn same_identity(&self, other: &AddressDetails) -> bool {
+        self.seed_name == other.seed_name
+            && self.path == other.path
+            && self.has_pwd == other.has_pwd
+            && self.network_id == other.network_id
+            && self.encryption == other.encryption
+    }
+}
+
+/// Watch-only account export
+///
+/// Contains only what is needed to track the address: public key,
+/// [`Encryption`] and network. Contains no seed name or derivation path, the
+/// recipient can not derive or sign anything with it.
+///
+/// Exported SCALE-encoded, through [`Encode`] and [`Decode`].
+#[derive(Decode, PartialEq, Eq, Encode, Debug, Clone)]
+pub struct WatchOnlyAccount {
+    /// public key
+    pub multisigner: MultiSigner,
+
+    /// encryption algorithm, distinguishes `Ethereum` from `Ecdsa`
+    pub encryption: Encryption,
+
+    /// network of the address, `None` for root address
+    pub network_id: Option<NetworkSpecsKey>,
+}
+
+/// Full derivation path for display, including the network `path_id`
+///
+/// Path stored in [`AddressDetails`] is already the full derivation path
+/// the public key was derived with, e.g. `//polkadot//0`, network `path_id`
+/// included, and is displayed as stored. Prepending `path_id` to it would
+/// display a path deriving a different key. Network specs are not needed
+/// for that, and are ignored.
+pub fn full_display_path(details: &AddressDetails, _specs: Option<&NetworkSpecs>) -> String {
+    details.path.to_string()
+}
+
+/// Check if any address of the seed `seed_name` in `addresses` has its
+/// secret exposed
+///
+/// Exposed secret of one address puts the whole seed under suspicion, so
+/// the addresses derived from the seed afterwards inherit the flag.
+pub fn seed_secret_exposed(seed_name: &str, addresses: &[AddressDetails]) -> bool {
+    addresses
+        .iter()
+        .any(|details| details.seed_name == seed_name && details.secret_exposed)
+}
+
+/// Difference between two sets of accounts, e.g. of the same wallet on two
+/// devices
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AccountSetDiff {
+    /// accounts found only in the first set
+    pub only_a: Vec<AddressDetails>,
+
+    /// accounts found only in the second set
+    pub only_b: Vec<AddressDetails>,
+
+    /// accounts found in both sets, as they are in the first set
+    pub common: Vec<AddressDetails>,
+}
+
+/// Compare two sets of accounts
+///
+/// Accounts are matched with [`AddressDetails::same_identity`], so the
+/// accounts differing only in mutable metadata, e.g. `secret_exposed`, are
+/// common. Order of the accounts in each output list follows the input.
+pub fn diff_account_sets(a: &[AddressDetails], b: &[AddressDetails]) -> AccountSetDiff {
+    let mut diff = AccountSetDiff::default();
+    for details in a {
+        if b.iter().any(|other| details.same_identity(other)) {
+            diff.common.push(details.to_owned());
+        } else {
+            diff.only_a.push(details.to_owned());
+        }
+    }
+    diff.only_b = b
+        .iter()
+        .filter(|details| !a.iter().any(|other| details.same_identity(other)))
+        .cloned()
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn westend_address(path: &str) -> AddressDetails {
+        let westend = NetworkSpecs::westend();
+        AddressDetails {
+            seed_name: String::from("Alice"),
+            path: String::from(path),
+            has_pwd: false,
+            network_id: Some(
+                NetworkSpecsKey::from_parts(&westend.genesis_hash, &westend.encryption).unwrap(),
+            ),
+            encryption: Encryption::Sr25519,
+            public_key: vec![0xab; 32],
+            secret_exposed: false,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_keys_as_hex() {
+        let address_details = westend_address("//westend");
+        let json = serde_json::to_value(&address_details).unwrap();
+        assert_eq!(json["public_key"], format!("0x{}", "ab".repeat(32)));
+        assert_eq!(
+            json["network_id"],
+            "0x01e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e"
+        );
+        assert_eq!(json["encryption"], "sr25519");
+        assert_eq!(
+            serde_json::from_value::<AddressDetails>(json).unwrap(),
+            address_details
+        );
+    }
+
+    #[test]
+    fn display_path_is_stored_path() {
+        let westend = NetworkSpecs::westend();
+        for stored in [
+            "//0",
+            "/soft",
+            "//westend",
+            "//westend//0",
+            "//westend2",
+            "",
+        ] {
+            assert_eq!(
+                full_display_path(&westend_address(stored), Some(&westend)),
+                stored
+            );
+            assert_eq!(full_display_path(&westend_address(stored), None), stored);
+        }
+        let root = AddressDetails {
+            network_id: None,
+            ..westend_address("//0")
+        };
+        assert_eq!(full_display_path(&root, Some(&westend)), "//0");
+    }
+
+    #[test]
+    fn display_path_derives_same_key() {
+        let westend = NetworkSpecs::westend();
+        let derive = |path: &str| {
+            crate::create_address_with_seed_phrase(
+                Some(&westend),
+                Encryption::Sr25519,
+                path,
+                "bottom drive obey lake curtain smoke basket hold race lonely fit walk",
+                "Alice",
+                None,
+                false,
+            )
+            .unwrap()
+        };
+        for path in ["//westend//0", "//0", "//Alice/soft"] {
+            let outcome = derive(path);
+            let displayed = full_display_path(outcome.details(), Some(&westend));
+            assert_eq!(
+                derive(&displayed).details().public_key,
+                outcome.details().public_key
+            );
+        }
+    }
+
+    #[test]
+    fn same_identity_ignores_mutable_fields() {
+        let address_details = westend_address("//0");
+        let exposed = AddressDetails {
+            secret_exposed: true,
+            public_key: vec![0xcd; 32],
+            ..westend_address("//0")
+        };
+        assert!(address_details.same_identity(&exposed));
+        assert_ne!(address_details, exposed);
+        let others = [
+            westend_address("//1"),
+            AddressDetails {
+                seed_name: String::from("Bob"),
+                ..westend_address("//0")
+            },
+            AddressDetails {
+                has_pwd: true,
+                ..westend_address("//0")
+            },
+            AddressDetails {
+                network_id: None,
+                ..westend_address("//0")
+            },
+            AddressDetails {
+                encryption: Encryption::Ed25519,
+                ..westend_address("//0")
+            },
+        ];
+        for other in others {
+            assert!(!address_details.same_identity(&other));
+        }
+    }
+
+    #[test]
+    fn watch_only_has_no_secrets() {
+        let address_details = westend_address("//secret_path");
+        let multisigner = MultiSigner::Sr25519(sp_core::sr25519::Public::from_raw([0xab; 32]));
+        let watch_only = address_details.to_watch_only(&multisigner);
+        assert_eq!(watch_only.multisigner, multisigner);
+        assert_eq!(watch_only.encryption, Encryption::Sr25519);
+        assert_eq!(watch_only.network_id, address_details.network_id);
+        let encoded = watch_only.encode();
+        for secret in [&b"Alice"[..], &b"secret_path"[..]] {
+            assert!(!encoded.windows(secret.len()).any(|window| window == secret));
+        }
+        assert_eq!(
+            WatchOnlyAccount::decode(&mut &encoded[..]).unwrap(),
+            watch_only
+        );
+    }
+
+    #[test]
+    fn address_string_per_encryption() {
+        let alice_sr25519 =
+            hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+                .unwrap();
+        let address_details = westend_address("//Alice");
+        assert_eq!(
+            address_details.address_string(&alice_sr25519, 42).unwrap(),
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        );
+        assert_eq!(
+            address_details.address_string(&alice_sr25519, 0).unwrap(),
+            "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+        );
+        let alice_ecdsa =
+            hex::decode("020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a1")
+                .unwrap();
+        let ethereum_details = AddressDetails {
+            encryption: Encryption::Ethereum,
+            network_id: None,
+            ..westend_address("//Alice")
+        };
+        assert_eq!(
+            ethereum_details.address_string(&alice_ecdsa, 42).unwrap(),
+            "0xE04CC55ebEE1cBCE552f250e85c57B70B2E2625b"
+        );
+        assert!(ethereum_details.address_string(&alice_sr25519, 42).is_err());
+    }
+
+    #[test]
+    fn account_sets_compared() {
+        let exposed = AddressDetails {
+            secret_exposed: true,
+            ..westend_address("//0")
+        };
+        let a = [
+            westend_address("//0"),
+            westend_address("//1"),
+            westend_address("//2"),
+        ];
+        let b = [westend_address("//3"), westend_address("//2"), exposed];
+        let diff = diff_account_sets(&a, &b);
+        assert_eq!(
+            diff,
+            AccountSetDiff {
+                only_a: vec![westend_address("//1")],
+                only_b: vec![westend_address("//3")],
+                common: vec![westend_address("//0"), westend_address("//2")],
+            }
+        );
+        assert_eq!(diff_account_sets(&[], &[]), AccountSetDiff::default());
+    }
+
+    #[test]
+    fn details_encryption_matches_network() {
+        westend_address("//0").validate().unwrap();
+        AddressDetails {
+            network_id: None,
+            encryption: Encryption::Ecdsa,
+            ..westend_address("//0")
+        }
+        .validate()
+        .unwrap();
+        let inconsistent = AddressDetails {
+            encryption: Encryption::Ed25519,
+            ..westend_address("//0")
+        };
+        assert!(matches!(
+            inconsistent.validate(),
+            Err(IdentityError::InconsistentEncryption {
+                details_encryption: Encryption::Ed25519,
+                network_encryption: Encryption::Sr25519,
+            })
+        ));
+    }
+
+    #[test]
+    fn legacy_encoded_details_decoded() {
+        // `Alice`, `//0`, no password, no network, `Sr25519`, not exposed, as
+        // encoded before `public_key` was added
+        let legacy = hex::decode("14416c6963650c2f2f3000000100").unwrap();
+        let public_key =
+            hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+                .unwrap();
+        let address_key = AddressKey::from_parts(&public_key, &Encryption::Sr25519, None).unwrap();
+        let details = AddressDetails::from_legacy_encoded(&legacy, &address_key).unwrap();
+        assert_eq!(
+            details,
+            AddressDetails {
+                network_id: None,
+                public_key,
+                ..westend_address("//0")
+            }
+        );
+        // current layout only appends the public key
+        let encoded = details.encode();
+        assert_eq!(&encoded[..legacy.len()], &legacy[..]);
+        assert_eq!(AddressDetails::decode(&mut &encoded[..]).unwrap(), details);
+        assert!(AddressDetails::decode(&mut &legacy[..]).is_err());
+        assert!(AddressDetails::from_legacy_encoded(&encoded, &address_key).is_err());
+    }
 }